@@ -1,14 +1,31 @@
 //! 通用工具库
+//!
+//! 默认启用 `std`。关闭默认 feature 并启用 `alloc` 时为 `no_std` 构建，
+//! 只提供 `vars` 中的 `ValueType`、`ValueDict`、`UpperKey` 等核心类型；
+//! 环境变量展开、文件加载、`CwdGuard` 与全局变量等依赖 `std` 的部分不可用。
+#![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(not(feature = "alloc"))]
+compile_error!("orion-variate requires the `std` or `alloc` feature");
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 pub mod opt;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod tpl;
 pub mod vars;
 
 // Re-export commonly used items from `vars` at the crate root for ergonomic imports
+#[cfg(feature = "std")]
 #[deprecated]
 pub use vars::EnvEvalable;
+#[cfg(feature = "std")]
 pub use vars::{
-    CwdGuard, EnvChecker, EnvDict, EnvEvaluable, Mutability, OriginDict, OriginValue, UpperKey,
-    ValueConstraint, ValueDict, ValueObj, ValueType, ValueVec, VarCollection, VarDefinition,
-    VarToValue, extract_env_var_names, find_project_define, find_project_define_base,
-    find_project_root, find_project_root_from, setup_start_env_vars,
+    CwdGuard, EnvChecker, EnvEvaluable, Mutability, OriginDict, OriginValue, ValueConstraint,
+    VarCollection, VarDefinition, VarToValue, extract_env_var_names, find_project_define,
+    find_project_define_base, find_project_root, find_project_root_from, setup_start_env_vars,
 };
+pub use vars::{EnvDict, UpperKey, ValueDict, ValueObj, ValueType, ValueVec};
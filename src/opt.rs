@@ -1,4 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use orion_error::{DomainReason, StructError};
 
 pub trait OptionFrom<T> {
     fn to_opt(self) -> Option<T>;
@@ -22,10 +24,114 @@ impl OptionFrom<PathBuf> for &str {
     }
 }
 
+impl OptionFrom<PathBuf> for String {
+    fn to_opt(self) -> Option<PathBuf> {
+        Some(PathBuf::from(self))
+    }
+}
+
+impl OptionFrom<PathBuf> for PathBuf {
+    fn to_opt(self) -> Option<PathBuf> {
+        Some(self)
+    }
+}
+
+impl OptionFrom<PathBuf> for &Path {
+    fn to_opt(self) -> Option<PathBuf> {
+        Some(self.to_path_buf())
+    }
+}
+
+impl OptionFrom<String> for Option<&str> {
+    fn to_opt(self) -> Option<String> {
+        self.map(str::to_string)
+    }
+}
+
+impl OptionFrom<PathBuf> for Option<&str> {
+    fn to_opt(self) -> Option<PathBuf> {
+        self.map(PathBuf::from)
+    }
+}
+
+impl OptionFrom<PathBuf> for Option<String> {
+    fn to_opt(self) -> Option<PathBuf> {
+        self.map(PathBuf::from)
+    }
+}
+
+macro_rules! impl_option_from_self {
+    ($($ty:ty),*) => {
+        $(
+            impl OptionFrom<$ty> for $ty {
+                fn to_opt(self) -> Option<$ty> {
+                    Some(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_option_from_self!(
+    bool, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64
+);
+
+/// 按条件将值转为 `Option`
+///
+/// 方法名不用 `take_if`，避免与 `Option::take_if(&mut self, ..)` 同名而语义不同。
+pub trait OptionSomeIf: Sized {
+    /// 满足条件时为 `Some(self)`，否则为 `None`
+    fn some_if<F: FnOnce(&Self) -> bool>(self, pred: F) -> Option<Self> {
+        pred(&self).then_some(self)
+    }
+}
+
+impl<T> OptionSomeIf for T {}
+
+/// 非空时为 `Some`，适用于把空字符串/空路径视为未设置的场景
+pub trait OptionNonEmpty: Sized {
+    fn non_empty(self) -> Option<Self>;
+}
+
+impl OptionNonEmpty for String {
+    fn non_empty(self) -> Option<Self> {
+        self.some_if(|s| !s.is_empty())
+    }
+}
+
+impl OptionNonEmpty for &str {
+    fn non_empty(self) -> Option<Self> {
+        self.some_if(|s| !s.is_empty())
+    }
+}
+
+impl OptionNonEmpty for PathBuf {
+    fn non_empty(self) -> Option<Self> {
+        self.some_if(|p| !p.as_os_str().is_empty())
+    }
+}
+
+/// 将 `Option` 转为带领域错误原因的 `Result`，如 `VarsResult`/`TplResult`
+pub trait OptionReason<T> {
+    fn ok_or_reason<R>(self, reason: R) -> Result<T, StructError<R>>
+    where
+        R: DomainReason,
+        StructError<R>: From<R>;
+}
+
+impl<T> OptionReason<T> for Option<T> {
+    fn ok_or_reason<R>(self, reason: R) -> Result<T, StructError<R>>
+    where
+        R: DomainReason,
+        StructError<R>: From<R>,
+    {
+        self.ok_or_else(|| StructError::from(reason))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_str_to_string_option() {
@@ -219,4 +325,45 @@ mod tests {
         let result = use_trait::<PathBuf, _>("/tmp/test");
         assert_eq!(result, Some(PathBuf::from("/tmp/test")));
     }
+
+    #[test]
+    fn test_extended_conversions() {
+        let result: Option<PathBuf> = String::from("/tmp/a").to_opt();
+        assert_eq!(result, Some(PathBuf::from("/tmp/a")));
+        let result: Option<PathBuf> = Path::new("/tmp/b").to_opt();
+        assert_eq!(result, Some(PathBuf::from("/tmp/b")));
+
+        let name: Option<&str> = Some("demo");
+        let result: Option<String> = name.to_opt();
+        assert_eq!(result, Some("demo".to_string()));
+        let none: Option<&str> = None;
+        let result: Option<PathBuf> = none.to_opt();
+        assert_eq!(result, None);
+
+        assert_eq!(8080u16.to_opt(), Some(8080u16));
+        assert_eq!(true.to_opt(), Some(true));
+        assert_eq!(1.5f64.to_opt(), Some(1.5));
+    }
+
+    #[test]
+    fn test_some_if_and_non_empty() {
+        assert_eq!(3u32.some_if(|x| *x > 2), Some(3));
+        assert_eq!(1u32.some_if(|x| *x > 2), None);
+        assert_eq!("".non_empty(), None);
+        assert_eq!("a".non_empty(), Some("a"));
+        assert_eq!(String::new().non_empty(), None);
+        assert_eq!(PathBuf::new().non_empty(), None);
+    }
+
+    #[test]
+    fn test_ok_or_reason() {
+        use crate::tpl::{TplReason, TplResult};
+        use crate::vars::{VarsReason, VarsResult};
+
+        let found: VarsResult<u32> = Some(1).ok_or_reason(VarsReason::UnKnow);
+        assert_eq!(found.ok(), Some(1));
+
+        let missing: TplResult<u32> = None.ok_or_reason(TplReason::Template("app.conf".into()));
+        assert!(missing.unwrap_err().to_string().contains("app.conf"));
+    }
 }
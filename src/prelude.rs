@@ -0,0 +1,19 @@
+//! 常用类型与 trait，一次导入
+//!
+//! `opt::OptionSomeIf` 为所有类型实现，不在此导出，需要时显式导入。
+//!
+//! ```
+//! use orion_variate::prelude::*;
+//!
+//! let mut dict = ValueDict::new();
+//! dict.insert("port", ValueType::from("8080"));
+//! assert_eq!(dict.try_get_typed::<u16>("PORT").unwrap(), Some(8080));
+//! ```
+
+pub use crate::opt::{OptionFrom, OptionNonEmpty, OptionReason};
+pub use crate::tpl::{TplEngine, TplReason, TplResult};
+pub use crate::vars::{
+    EnvChecker, EnvDict, EnvEvaluable, FromValue, Mutability, OriginDict, OriginValue, UpperKey,
+    ValueDict, ValueObj, ValueType, ValueVec, VarCollection, VarDefinition, VarToValue, VarsFormat,
+    VarsReason, VarsResult,
+};
@@ -0,0 +1,272 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use derive_getters::Getters;
+use indexmap::IndexMap;
+
+use crate::vars::ValueDict;
+
+use super::{engine::TplEngine, error::TplResult};
+
+/// 缓存命中统计
+#[derive(Getters, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    hits: u64,
+    misses: u64,
+}
+
+fn hash_str(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+// 排序后序列化，字典插入顺序不同但内容相同时得到相同哈希；序列化失败时不缓存
+fn hash_dict(dict: &ValueDict) -> Option<u64> {
+    serde_json::to_string(&dict.sorted())
+        .ok()
+        .map(|json| hash_str(&json))
+}
+
+type Outputs = IndexMap<(u64, u64), String>;
+
+// `CachedEngine::new` 默认最多缓存的输出条数
+const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+/// 为任意 `TplEngine` 增加渲染缓存
+///
+/// 以（模板内容哈希，字典内容哈希）为键缓存输出，模板与变量都未变化时直接返回上次结果。
+/// 模板之间可能通过 `include`/`extends` 相互引用，因此任何 `add_template` 都会清空已缓存的输出。
+/// 超过容量时淘汰最早写入的条目；缓存只在进程内有效，`clear()` 清空。
+pub struct CachedEngine<E> {
+    inner: E,
+    // 命名模板 → 内容哈希
+    templates: HashMap<String, u64>,
+    outputs: Mutex<Outputs>,
+    capacity: usize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+fn store(outputs: &mut Outputs, capacity: usize, key: (u64, u64), out: &str) {
+    if capacity == 0 {
+        return;
+    }
+    while outputs.len() >= capacity {
+        outputs.shift_remove_index(0);
+    }
+    outputs.insert(key, out.to_string());
+}
+
+impl<E: TplEngine> CachedEngine<E> {
+    /// 最多缓存 256 条输出
+    pub fn new(inner: E) -> Self {
+        Self::with_capacity(inner, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// 最多缓存 `capacity` 条输出，为 0 时不缓存
+    pub fn with_capacity(inner: E, capacity: usize) -> Self {
+        Self {
+            inner,
+            templates: HashMap::new(),
+            outputs: Mutex::new(IndexMap::new()),
+            capacity,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.outputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    fn cached(
+        &self,
+        key: Option<(u64, u64)>,
+        render: impl FnOnce() -> TplResult<String>,
+    ) -> TplResult<String> {
+        let Some(key) = key else {
+            return render();
+        };
+        if let Some(out) = self
+            .outputs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(out.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let out = render()?;
+        let mut outputs = self.outputs.lock().unwrap_or_else(|e| e.into_inner());
+        store(&mut outputs, self.capacity, key, &out);
+        Ok(out)
+    }
+}
+
+impl<E: TplEngine> TplEngine for CachedEngine<E> {
+    fn add_template(&mut self, name: &str, content: &str) -> TplResult<()> {
+        self.inner.add_template(name, content)?;
+        self.templates.insert(name.to_string(), hash_str(content));
+        // 其他模板可能 include/extends 该模板，已缓存的输出全部失效
+        self.outputs
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+        Ok(())
+    }
+
+    fn render(&self, name: &str, dict: &ValueDict) -> TplResult<String> {
+        // 未经本包装注册的模板无法得知内容，不缓存
+        let Some(tpl_hash) = self.templates.get(name) else {
+            return self.inner.render(name, dict);
+        };
+        let key = hash_dict(dict).map(|dict_hash| (*tpl_hash, dict_hash));
+        self.cached(key, || self.inner.render(name, dict))
+    }
+
+    fn render_str(&mut self, content: &str, dict: &ValueDict) -> TplResult<String> {
+        let Some(dict_hash) = hash_dict(dict) else {
+            return self.inner.render_str(content, dict);
+        };
+        let key = (hash_str(content), dict_hash);
+        let outputs = self.outputs.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Some(out) = outputs.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(out.clone());
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let out = self.inner.render_str(content, dict)?;
+        let outputs = self.outputs.get_mut().unwrap_or_else(|e| e.into_inner());
+        store(outputs, self.capacity, key, &out);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueType;
+
+    // 简单的 `{{KEY}}` 替换引擎，记录实际渲染次数
+    #[derive(Default)]
+    struct CountingEngine {
+        templates: HashMap<String, String>,
+        renders: std::cell::Cell<u32>,
+    }
+
+    impl CountingEngine {
+        fn fill(&self, content: &str, dict: &ValueDict) -> String {
+            self.renders.set(self.renders.get() + 1);
+            dict.iter().fold(content.to_string(), |acc, (k, v)| {
+                acc.replace(&format!("{{{{{}}}}}", k.as_str()), &v.to_string())
+            })
+        }
+    }
+
+    impl TplEngine for CountingEngine {
+        fn add_template(&mut self, name: &str, content: &str) -> TplResult<()> {
+            self.templates.insert(name.into(), content.into());
+            Ok(())
+        }
+        fn render(&self, name: &str, dict: &ValueDict) -> TplResult<String> {
+            // `{{>name}}` 模拟 include
+            let mut content = self.templates[name].clone();
+            for (partial, body) in &self.templates {
+                content = content.replace(&format!("{{{{>{partial}}}}}"), body);
+            }
+            Ok(self.fill(&content, dict))
+        }
+        fn render_str(&mut self, content: &str, dict: &ValueDict) -> TplResult<String> {
+            Ok(self.fill(content, dict))
+        }
+    }
+
+    #[test]
+    fn test_render_cache() {
+        let mut engine = CachedEngine::new(CountingEngine::default());
+        engine.add_template("app", "host={{HOST}}").unwrap();
+
+        let mut dict = ValueDict::new();
+        dict.insert("host", ValueType::from("a"));
+        dict.insert("port", ValueType::from(80u64));
+        assert_eq!(engine.render("app", &dict).unwrap(), "host=a");
+        assert_eq!(engine.render("app", &dict).unwrap(), "host=a");
+
+        // 内容相同、顺序不同的字典命中缓存
+        let mut reordered = ValueDict::new();
+        reordered.insert("port", ValueType::from(80u64));
+        reordered.insert("host", ValueType::from("a"));
+        engine.render("app", &reordered).unwrap();
+        assert_eq!(engine.stats(), CacheStats { hits: 2, misses: 1 });
+
+        dict.insert("host", ValueType::from("b"));
+        assert_eq!(engine.render("app", &dict).unwrap(), "host=b");
+        assert_eq!(engine.render_str("{{PORT}}", &dict).unwrap(), "80");
+        assert_eq!(engine.render_str("{{PORT}}", &dict).unwrap(), "80");
+        assert_eq!(engine.stats(), CacheStats { hits: 3, misses: 3 });
+
+        // 重新注册模板后内容哈希改变
+        engine.add_template("app", "h={{HOST}}").unwrap();
+        assert_eq!(engine.render("app", &dict).unwrap(), "h=b");
+
+        engine.clear();
+        engine.render_str("{{PORT}}", &dict).unwrap();
+        assert_eq!(engine.into_inner().renders.get(), 5);
+    }
+
+    #[test]
+    fn test_cache_invalidated_by_partial() {
+        let mut engine = CachedEngine::new(CountingEngine::default());
+        engine.add_template("header", "v1").unwrap();
+        engine.add_template("page", "{{>header}}:{{HOST}}").unwrap();
+        let mut dict = ValueDict::new();
+        dict.insert("host", ValueType::from("a"));
+        assert_eq!(engine.render("page", &dict).unwrap(), "v1:a");
+
+        // 只修改被引用的模板，依赖它的输出不能命中旧缓存
+        engine.add_template("header", "v2").unwrap();
+        assert_eq!(engine.render("page", &dict).unwrap(), "v2:a");
+        assert_eq!(engine.stats(), CacheStats { hits: 0, misses: 2 });
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let mut engine = CachedEngine::with_capacity(CountingEngine::default(), 2);
+        let dict = ValueDict::new();
+        for content in ["a", "b", "c", "a"] {
+            engine.render_str(content, &dict).unwrap();
+        }
+        // `a` 在写入 `c` 时被淘汰
+        assert_eq!(engine.stats(), CacheStats { hits: 0, misses: 4 });
+        engine.render_str("a", &dict).unwrap();
+        assert_eq!(engine.stats().hits(), &1);
+        assert_eq!(engine.outputs.lock().unwrap().len(), 2);
+
+        let mut engine = CachedEngine::with_capacity(CountingEngine::default(), 0);
+        engine.render_str("a", &dict).unwrap();
+        engine.render_str("a", &dict).unwrap();
+        assert_eq!(engine.into_inner().renders.get(), 2);
+    }
+}
@@ -0,0 +1,348 @@
+use derive_getters::Getters;
+use orion_error::StructError;
+
+use crate::vars::{UpperKey, VarCollection};
+
+use super::error::{TplReason, TplResult};
+
+const KEYWORDS: &[&str] = &[
+    "and", "or", "not", "in", "is", "true", "false", "True", "False", "loop", "self", "super",
+];
+
+fn push_unique(list: &mut Vec<String>, item: &str) {
+    if !list.iter().any(|x| x == item) {
+        list.push(item.to_string());
+    }
+}
+
+// 扫描一段表达式中的变量路径（如 `A`、`DB.host`），跳过字符串、过滤器、测试名
+// （`is defined`）、函数名、命名参数与 `locals` 中的局部名称
+fn expr_refs(expr: &str, locals: &[Vec<String>], refs: &mut Vec<String>) {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    let mut after_pipe = false;
+    let mut after_is = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            i += 1;
+            after_pipe = false;
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let path: String = chars[start..i].iter().collect();
+            let path = path.trim_end_matches('.');
+            let mut j = i;
+            while j < chars.len() && chars[j].is_whitespace() {
+                j += 1;
+            }
+            let next = chars.get(j).copied();
+            let named_arg = next == Some('=') && chars.get(j + 1) != Some(&'=');
+            let call = next == Some('(');
+            let head = path.split('.').next().unwrap_or(path);
+            let test_name = after_is && path != "not";
+            let local = locals.iter().flatten().any(|l| l == head);
+            if !(after_pipe || test_name || named_arg || call || local || KEYWORDS.contains(&head))
+            {
+                push_unique(refs, path);
+            }
+            after_is = path == "is" || (after_is && path == "not");
+            after_pipe = false;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            continue;
+        }
+        if !c.is_whitespace() {
+            after_pipe = c == '|' && chars.get(i + 1) != Some(&'|');
+        }
+        i += 1;
+    }
+}
+
+// 标签中表达式的起始位置，以及 `for k, v in X`、`set x = ...`、`macro m(a, b=1)`、
+// `import "m" as m`、`from "m" import a, b as c` 引入的局部名称
+fn tag_locals(tag: &str, body: &str) -> (usize, Vec<String>) {
+    let names = |list: &str| -> Vec<String> {
+        list.split(',')
+            .map(|name| name.split('=').next().unwrap_or(name).trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect()
+    };
+    match tag {
+        "for" => match body.find(" in ") {
+            Some(pos) => (pos + " in ".len(), names(&body[..pos])),
+            None => (body.len(), Vec::new()),
+        },
+        "set" | "set_global" => match body.find('=') {
+            Some(pos) => (pos + 1, names(&body[..pos])),
+            None => (body.len(), Vec::new()),
+        },
+        "macro" => {
+            let args = body
+                .split_once('(')
+                .and_then(|(_, rest)| rest.rsplit_once(')'))
+                .map(|(args, _)| names(args))
+                .unwrap_or_default();
+            (body.len(), args)
+        }
+        "import" => {
+            let alias = body.rsplit_once(" as ").map(|(_, alias)| names(alias));
+            (body.len(), alias.unwrap_or_default())
+        }
+        "from" => {
+            let imported = body.split_once(" import ").map(|(_, list)| {
+                list.split(',')
+                    .filter_map(|item| item.rsplit(" as ").next())
+                    .flat_map(names)
+                    .collect()
+            });
+            (body.len(), imported.unwrap_or_default())
+        }
+        "if" | "elif" => (0, Vec::new()),
+        // filter 名称、include/extends/block 等不含变量引用
+        _ => (body.len(), Vec::new()),
+    }
+}
+
+/// 模板中引用的变量路径，按首次出现顺序，不含局部名称
+///
+/// 识别 `{{ ... }}` 输出与 `{% if/elif/for/set ... %}` 中的表达式，跳过 `{# #}` 注释
+/// 与 `{% raw %}...{% endraw %}` 中的内容。`import`/`from ... import` 引入的名称
+/// （如 `m::link()` 中的 `m`）作用于整个模板。
+/// `for` 循环变量与 `macro` 参数只在对应块内视为局部名称，块内的 `set` 同样只在该块生效；
+/// 块外的 `set` 与 `set_global` 从定义处起作用于模板其余部分。
+pub fn template_refs(content: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    // 局部名称栈：底层为模板级，每个 for/macro 块压入一层
+    let mut frames: Vec<Vec<String>> = vec![Vec::new()];
+    let mut rest = content;
+    while let Some(pos) = rest.find('{') {
+        let open = &rest[pos..];
+        let close = match open.get(..2) {
+            Some("{{") => "}}",
+            Some("{%") => "%}",
+            Some("{#") => "#}",
+            _ => {
+                rest = &open[1..];
+                continue;
+            }
+        };
+        let Some(end) = open[2..].find(close) else {
+            break;
+        };
+        let inner = open[2..2 + end].trim_matches(['-', ' ', '\t', '\n', '\r']);
+        match close {
+            "}}" => expr_refs(inner, &frames, &mut refs),
+            "%}" => {
+                let (tag, body) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+                let (from, names) = tag_locals(tag, body);
+                expr_refs(&body[from..], &frames, &mut refs);
+                match tag {
+                    "for" | "macro" => frames.push(names),
+                    "endfor" | "endmacro" if frames.len() > 1 => {
+                        frames.pop();
+                    }
+                    "set_global" | "import" | "from" => frames[0].extend(names),
+                    "raw" => {
+                        rest = skip_raw(&open[2 + end + 2..]);
+                        continue;
+                    }
+                    "set" => {
+                        let top = frames.len() - 1;
+                        frames[top].extend(names);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        rest = &open[2 + end + 2..];
+    }
+    refs
+}
+
+// 跳过 `{% raw %}` 之后直到 `{% endraw %}`（含）的内容
+fn skip_raw(text: &str) -> &str {
+    let mut rest = text;
+    while let Some(pos) = rest.find("{%") {
+        let open = &rest[pos + 2..];
+        let Some(end) = open.find("%}") else {
+            break;
+        };
+        if open[..end].trim_matches(['-', ' ', '\t', '\n', '\r']) == "endraw" {
+            return &open[end + 2..];
+        }
+        rest = &open[end + 2..];
+    }
+    ""
+}
+
+/// 模板与变量定义的对照结果
+#[derive(Getters, Clone, Debug, Default, PartialEq)]
+pub struct TplCheckReport {
+    /// 模板引用但未定义的变量
+    undefined: Vec<String>,
+    /// 模板引用的已废弃变量，或使用 `renamed_from` 旧名称的引用
+    deprecated: Vec<String>,
+    /// 已定义但没有任何模板引用的变量
+    unused: Vec<String>,
+}
+
+impl TplCheckReport {
+    /// 存在未定义引用时返回 `TplReason::Undefined`
+    pub fn ensure_defined(&self) -> TplResult<()> {
+        if self.undefined.is_empty() {
+            return Ok(());
+        }
+        Err(StructError::from(TplReason::Undefined(
+            self.undefined.join(", "),
+        )))
+    }
+}
+
+/// 渲染前静态检查：对照 `vars` 找出模板中未定义、已废弃的引用与未被使用的定义
+///
+/// 引用 `A.b` 先按完整名称（命名空间变量）查找，再按首段（对象变量）查找；大小写不敏感。
+pub fn check_templates<'a, I>(templates: I, vars: &VarCollection) -> TplCheckReport
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut report = TplCheckReport::default();
+    let mut used = Vec::new();
+    for content in templates {
+        for path in template_refs(content) {
+            let head = path.split('.').next().unwrap_or(&path);
+            let Some(var) = vars.find(&path).or_else(|| vars.find(head)) else {
+                push_unique(&mut report.undefined, &path);
+                continue;
+            };
+            let name = UpperKey::fold(var.name());
+            let legacy = name != UpperKey::fold(&path) && name != UpperKey::fold(head);
+            if legacy || var.is_deprecated() {
+                push_unique(&mut report.deprecated, &path);
+            }
+            push_unique(&mut used, var.name());
+        }
+    }
+    for (_, var) in vars.iter_all() {
+        if !used.contains(var.name()) {
+            push_unique(&mut report.unused, var.name());
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::{ValueType, VarDefinition};
+
+    #[test]
+    fn test_template_refs() {
+        let tpl = r#"
+{# {{ IGNORED }} #}
+listen {{ HOST }}:{{ PORT | default(value=DEFAULT_PORT) }}
+{% if ENV == "prod" and not DEBUG %}prod{% endif %}
+{% for item in ITEMS %}{{ item.name | upper }} {{ loop.index }}{% endfor %}
+{% set url = "http://" ~ DB.host %}{{ url }} {{ APP.PORT }} {{ now() }}
+"#;
+        assert_eq!(
+            template_refs(tpl),
+            vec![
+                "HOST",
+                "PORT",
+                "DEFAULT_PORT",
+                "ENV",
+                "DEBUG",
+                "ITEMS",
+                "DB.host",
+                "APP.PORT"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_refs_tests_and_filters() {
+        let tpl = r#"
+{% if TLS is defined and PROXY is not none %}{{ TLS }}{% endif %}
+{% filter upper %}{{ NAME }}{% endfilter %}
+{% if COUNT is divisibleby(3) %}x{% endif %}
+"#;
+        assert_eq!(template_refs(tpl), vec!["TLS", "PROXY", "NAME", "COUNT"]);
+    }
+
+    #[test]
+    fn test_template_refs_block_locals() {
+        let tpl = r#"
+{% for host in HOSTS %}{% set port = host.port %}{{ host.name }}:{{ port }}{% endfor %}
+{{ host }} {{ port }}
+{% macro link(url, label="x") %}{{ url }} {{ label }} {{ BASE }}{% endmacro %}
+{{ url }}
+{% set_global total = 0 %}{{ total }}
+"#;
+        assert_eq!(
+            template_refs(tpl),
+            vec!["HOSTS", "host", "port", "BASE", "url"]
+        );
+    }
+
+    #[test]
+    fn test_template_refs_raw_and_imports() {
+        let tpl = r#"
+{% raw %}{{ LITERAL }} {% if X %}{% endraw %}{{ AFTER }}
+{%- raw -%}{{ ALSO_LITERAL }}{%- endraw -%}
+{% import "macros.html" as m %}{{ m::link(url=SITE) }}
+{% from "forms.html" import input, label as lbl %}{{ input(NAME) }} {{ lbl }}
+"#;
+        assert_eq!(template_refs(tpl), vec!["AFTER", "SITE", "NAME"]);
+    }
+
+    #[test]
+    fn test_check_templates() {
+        let mut db = crate::vars::ValueObj::new();
+        db.insert("host".into(), ValueType::from("localhost"));
+        let vars = VarCollection::define(vec![
+            VarDefinition::from(("host", "0.0.0.0")),
+            VarDefinition::from(("http_port", 80u64)).with_renamed_from(vec!["port".into()]),
+            VarDefinition::from(("debug", false)).with_deprecated(Some("use LOG_LEVEL".into())),
+            VarDefinition::new("db", ValueType::Obj(db)),
+            VarDefinition::from(("app.name", "demo")),
+            VarDefinition::from(("replicas", 3u64)),
+        ]);
+        let report = check_templates(
+            [
+                "{{ HOST }}:{{ PORT }} {% if DEBUG %}x{% endif %}",
+                "{{ DB.host }} {{ APP.NAME }} {{ REGION }}",
+            ],
+            &vars,
+        );
+        assert_eq!(report.undefined(), &vec!["REGION".to_string()]);
+        assert_eq!(
+            report.deprecated(),
+            &vec!["PORT".to_string(), "DEBUG".to_string()]
+        );
+        assert_eq!(report.unused(), &vec!["replicas".to_string()]);
+
+        let err = report.ensure_defined().unwrap_err();
+        assert!(err.to_string().contains("undefined: REGION"));
+        assert!(
+            check_templates(["{{ HOST }}"], &vars)
+                .ensure_defined()
+                .is_ok()
+        );
+    }
+}
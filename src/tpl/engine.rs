@@ -0,0 +1,15 @@
+use crate::vars::ValueDict;
+
+use super::error::TplResult;
+
+/// 模板渲染引擎
+///
+/// 变量来自 `ValueDict`，键均为大写（如 `{{ HOST }}`）。
+pub trait TplEngine {
+    /// 注册命名模板，同名模板会被替换
+    fn add_template(&mut self, name: &str, content: &str) -> TplResult<()>;
+    /// 渲染已注册的模板
+    fn render(&self, name: &str, dict: &ValueDict) -> TplResult<String>;
+    /// 直接渲染一段模板内容，不注册
+    fn render_str(&mut self, content: &str, dict: &ValueDict) -> TplResult<String>;
+}
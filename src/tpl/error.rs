@@ -0,0 +1,31 @@
+use derive_more::From;
+use orion_error::{ErrorCode, StructError, UvsReason};
+use serde_derive::Serialize;
+use thiserror::Error;
+#[derive(Clone, Debug, Serialize, PartialEq, Error, From)]
+pub enum TplReason {
+    #[error("template: {0}")]
+    #[from(skip)]
+    Template(String),
+    #[error("render: {0}")]
+    #[from(skip)]
+    Render(String),
+    #[error("undefined: {0}")]
+    #[from(skip)]
+    Undefined(String),
+    #[error("{0}")]
+    Uvs(UvsReason),
+}
+
+impl ErrorCode for TplReason {
+    fn error_code(&self) -> i32 {
+        match self {
+            TplReason::Template(_) => 601,
+            TplReason::Render(_) => 602,
+            TplReason::Undefined(_) => 603,
+            TplReason::Uvs(r) => r.error_code(),
+        }
+    }
+}
+
+pub type TplResult<T> = Result<T, StructError<TplReason>>;
@@ -0,0 +1,12 @@
+mod cache;
+mod check;
+mod engine;
+mod error;
+#[cfg(feature = "tera")]
+mod tera_engine;
+pub use cache::{CacheStats, CachedEngine};
+pub use check::{TplCheckReport, check_templates, template_refs};
+pub use engine::TplEngine;
+pub use error::{TplReason, TplResult};
+#[cfg(feature = "tera")]
+pub use tera_engine::TeraEngine;
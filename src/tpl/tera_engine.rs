@@ -0,0 +1,113 @@
+use std::error::Error;
+
+use orion_error::StructError;
+use tera::{Context, Tera};
+
+use crate::vars::ValueDict;
+
+use super::{
+    engine::TplEngine,
+    error::{TplReason, TplResult},
+};
+
+/// 基于 Tera 的渲染引擎，支持循环、条件与过滤器
+#[derive(Default)]
+pub struct TeraEngine {
+    tera: Tera,
+}
+
+impl TeraEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Tera 的错误信息分散在 source 链中，拼接后才有定位价值
+fn error_chain(e: &tera::Error) -> String {
+    let mut msg = e.to_string();
+    let mut cur = e.source();
+    while let Some(x) = cur {
+        msg.push_str(&format!(": {x}"));
+        cur = x.source();
+    }
+    msg
+}
+
+fn dict_context(dict: &ValueDict) -> TplResult<Context> {
+    Context::from_serialize(dict).map_err(|e| StructError::from(TplReason::Render(error_chain(&e))))
+}
+
+impl TplEngine for TeraEngine {
+    fn add_template(&mut self, name: &str, content: &str) -> TplResult<()> {
+        self.tera
+            .add_raw_template(name, content)
+            .map_err(|e| StructError::from(TplReason::Template(error_chain(&e))))
+    }
+
+    fn render(&self, name: &str, dict: &ValueDict) -> TplResult<String> {
+        let ctx = dict_context(dict)?;
+        self.tera
+            .render(name, &ctx)
+            .map_err(|e| StructError::from(TplReason::Render(error_chain(&e))))
+    }
+
+    fn render_str(&mut self, content: &str, dict: &ValueDict) -> TplResult<String> {
+        let ctx = dict_context(dict)?;
+        self.tera
+            .render_str(content, &ctx)
+            .map_err(|e| StructError::from(TplReason::Render(error_chain(&e))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::{ValueObj, ValueType};
+
+    fn sample_dict() -> ValueDict {
+        let mut dict = ValueDict::new();
+        dict.insert("name", ValueType::from("galaxy"));
+        dict.insert("debug", ValueType::from(true));
+        dict.insert(
+            "ports",
+            ValueType::List(vec![ValueType::from(80u64), ValueType::from(443u64)]),
+        );
+        let mut db = ValueObj::new();
+        db.insert("host".into(), ValueType::from("127.0.0.1"));
+        dict.insert("db", ValueType::Obj(db));
+        dict
+    }
+
+    #[test]
+    fn test_render_str() {
+        let mut engine = TeraEngine::new();
+        let out = engine
+            .render_str(
+                "{{ NAME | upper }}{% if DEBUG %}-debug{% endif %}:{% for p in PORTS %}{{ p }},{% endfor %}{{ DB.host }}",
+                &sample_dict(),
+            )
+            .unwrap();
+        assert_eq!(out, "GALAXY-debug:80,443,127.0.0.1");
+    }
+
+    #[test]
+    fn test_named_template() {
+        let mut engine = TeraEngine::new();
+        engine.add_template("app.conf", "name={{ NAME }}").unwrap();
+        assert_eq!(
+            engine.render("app.conf", &sample_dict()).unwrap(),
+            "name=galaxy"
+        );
+        assert!(engine.render("missing", &sample_dict()).is_err());
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let mut engine = TeraEngine::new();
+        assert!(engine.add_template("bad", "{% if %}").is_err());
+        let err = engine
+            .render_str("{{ UNDEFINED }}", &sample_dict())
+            .unwrap_err();
+        assert!(err.to_string().contains("UNDEFINED"));
+    }
+}
@@ -0,0 +1,143 @@
+use std::{fs, path::Path};
+
+use orion_error::{ErrorOwe, ErrorWith};
+
+use super::{
+    ValueDict, ValueObj, ValueType, VarsFormat,
+    error::{VarsReason, VarsResult},
+};
+
+impl ValueType {
+    /// 规范形式：对象键排序，无小数部分、且在 `u64` 范围内的非负浮点（含 `-0.0`）转为整数
+    ///
+    /// 声明为 `ValueKind::Float` 的 schema / items 同样接受整数，重新加载后校验结果不变。
+    pub fn canonicalize(&self) -> ValueType {
+        match self {
+            ValueType::Float(f) if *f >= 0.0 && f.fract() == 0.0 && *f < u64::MAX as f64 => {
+                ValueType::Number(*f as u64)
+            }
+            ValueType::Obj(obj) => {
+                let mut obj: ValueObj = obj
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.canonicalize()))
+                    .collect();
+                obj.sort_unstable_keys();
+                ValueType::Obj(obj)
+            }
+            ValueType::List(list) => {
+                ValueType::List(list.iter().map(ValueType::canonicalize).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+impl ValueDict {
+    /// 规范形式：键按字典序排列，值见 [`ValueType::canonicalize`]
+    ///
+    /// 内容相同的字典在不同机器、不同合并顺序下得到相同结果，适合生成存入 Git 的变量文件。
+    pub fn canonicalize(&self) -> ValueDict {
+        let mut dict = ValueDict::new();
+        for (k, v) in self.sorted().iter() {
+            dict.insert(k.clone(), v.canonicalize());
+        }
+        dict
+    }
+
+    /// 以规范形式序列化，输出以单个换行结尾
+    pub fn dump(&self, format: VarsFormat) -> VarsResult<String> {
+        let dict = self.canonicalize();
+        let mut out = match format {
+            VarsFormat::Yaml => serde_yaml::to_string(&dict).owe(VarsReason::Format)?,
+            VarsFormat::Toml => toml::to_string(&dict).owe(VarsReason::Format)?,
+            VarsFormat::Json => serde_json::to_string_pretty(&dict).owe(VarsReason::Format)?,
+        };
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        Ok(out)
+    }
+
+    /// 以规范形式写入文件，格式由扩展名决定（yml/yaml/toml/json）
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> VarsResult<()> {
+        let path = path.as_ref();
+        let content = self.dump(VarsFormat::from_path(path)?)?;
+        fs::write(path, content)
+            .owe_sys()
+            .want("save vars dict")
+            .with(path.display().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_canonicalize() {
+        let mut inner = ValueObj::new();
+        inner.insert("b".into(), ValueType::Float(2.0));
+        inner.insert("a".into(), ValueType::Float(-0.0));
+        let mut left = ValueDict::new();
+        left.insert("z", ValueType::Obj(inner));
+        left.insert("port", ValueType::Float(8080.0));
+        left.insert("ratio", ValueType::Float(0.5));
+
+        let mut inner = ValueObj::new();
+        inner.insert("a".into(), ValueType::Float(0.0));
+        inner.insert("b".into(), ValueType::Number(2));
+        let mut right = ValueDict::new();
+        right.insert("ratio", ValueType::Float(0.5));
+        right.insert("port", ValueType::Number(8080));
+        right.insert("z", ValueType::Obj(inner));
+
+        assert_eq!(left.canonicalize(), right.canonicalize());
+        assert_eq!(
+            left.canonicalize().get("PORT"),
+            Some(&ValueType::Number(8080))
+        );
+        for format in [VarsFormat::Yaml, VarsFormat::Toml, VarsFormat::Json] {
+            let out = left.dump(format).unwrap();
+            assert_eq!(out, right.dump(format).unwrap());
+            assert!(out.ends_with('\n') && !out.ends_with("\n\n"));
+        }
+        assert_eq!(
+            left.dump(VarsFormat::Yaml).unwrap(),
+            "PORT: 8080\nRATIO: 0.5\nZ:\n  a: 0\n  b: 2\n"
+        );
+
+        // 2^64 超出 u64，保持浮点而不是饱和为 u64::MAX
+        let big = ValueType::Float(18446744073709551616.0);
+        assert_eq!(big.canonicalize(), big);
+    }
+
+    #[test]
+    fn test_canonical_float_still_validates() {
+        use crate::vars::{ListSchema, ValueKind, VarDefinition};
+
+        let list = ValueType::List(vec![ValueType::Float(1.0), ValueType::Float(2.5)]);
+        let var = VarDefinition::new("weights", list.clone())
+            .with_items(Some(ListSchema::new(ValueKind::Float)));
+        let canonical = list.canonicalize();
+        assert_eq!(
+            canonical,
+            ValueType::List(vec![ValueType::Number(1), ValueType::Float(2.5)])
+        );
+        assert!(var.validate_value("WEIGHTS", &canonical).is_ok());
+    }
+
+    #[test]
+    fn test_save_to() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vars.json");
+        let mut dict = ValueDict::new();
+        dict.insert("b", ValueType::from("x"));
+        dict.insert("a", ValueType::Float(1.0));
+        dict.save_to(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "{\n  \"A\": 1,\n  \"B\": \"x\"\n}\n");
+        let loaded: ValueDict = serde_json::from_str(&content).unwrap();
+        assert_eq!(loaded, dict.canonicalize());
+        assert!(dict.save_to(dir.path().join("vars.ini")).is_err());
+    }
+}
@@ -1,15 +1,46 @@
+use std::fmt::{Display, Formatter};
+
 use getset::Getters;
 use indexmap::IndexMap;
+use orion_error::StructError;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::vars::VarToValue;
 
-use super::{ValueDict, VarDefinition, definition::Mutability};
+use super::{
+    EnvDict, FieldSchema, UpperKey, ValueDict, ValueKind, ValueObj, ValueType, VarDefinition,
+    definition::Mutability,
+    error::{VarsReason, VarsResult},
+};
+
+/// 合并时名称仅大小写不同的两个定义，如 `foo` 与 `FOO`
+///
+/// 变量名按大写折叠，这类定义会静默互相覆盖。
+#[derive(Getters, Clone, Debug, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct CaseCollision {
+    existing: String,
+    incoming: String,
+}
+
+impl Display for CaseCollision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` collides with existing `{}` (names differ only in case)",
+            self.incoming, self.existing
+        )
+    }
+}
 
 #[derive(Getters, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 #[getset(get = "pub")]
 //#[serde(transparent)]
 pub struct VarCollection {
+    /// 待包含的其他变量文件，由 `VarCollection::load` 解析后清空
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<String>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty", rename = "immutable")]
     immutable_vars: Vec<VarDefinition>,
 
@@ -51,11 +82,91 @@ impl VarCollection {
             }
         }
         Self {
+            include: Vec::new(),
             immutable_vars,
             system_vars,
             module_vars,
         }
     }
+    /// 按作用域（immutable → system → module）遍历全部定义，各作用域内保持声明顺序
+    ///
+    /// 说明、默认值、示例与取值约束通过 `VarDefinition` 的 `desc()`/`value()`/`example()`/`constraint()` 读取。
+    pub fn iter_all(&self) -> impl Iterator<Item = (Mutability, &VarDefinition)> {
+        let immutable = self
+            .immutable_vars
+            .iter()
+            .map(|v| (Mutability::Immutable, v));
+        let system = self.system_vars.iter().map(|v| (Mutability::System, v));
+        let module = self.module_vars.iter().map(|v| (Mutability::Module, v));
+        immutable.chain(system).chain(module)
+    }
+
+    pub(crate) fn iter_all_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (Mutability, &mut VarDefinition)> {
+        let immutable = self
+            .immutable_vars
+            .iter_mut()
+            .map(|v| (Mutability::Immutable, v));
+        let system = self.system_vars.iter_mut().map(|v| (Mutability::System, v));
+        let module = self.module_vars.iter_mut().map(|v| (Mutability::Module, v));
+        immutable.chain(system).chain(module)
+    }
+
+    /// 覆盖各作用域与常用字段的示例集合，用于生成示例配置
+    pub fn example() -> Self {
+        let mut db = ValueObj::new();
+        db.insert("host".into(), ValueType::from("127.0.0.1"));
+        db.insert("port".into(), ValueType::from(3306u64));
+        VarCollection::define(vec![
+            VarDefinition::from(("env", "dev"))
+                .with_desc(Some("deploy environment".into()))
+                .with_mutability(Mutability::Immutable),
+            VarDefinition::from(("workers", 4u64))
+                .with_desc(Some("worker count".into()))
+                .with_example(Some(ValueType::from(8u64)))
+                .with_mutability(Mutability::System),
+            VarDefinition::from(("log_level", "info"))
+                .with_desc(Some("log level".into()))
+                .with_mutability(Mutability::Module),
+            VarDefinition::new("db", ValueType::Obj(db))
+                .with_desc(Some("database connection".into()))
+                .with_schema(Some(
+                    vec![
+                        FieldSchema::new("host", ValueKind::String).with_required(true),
+                        FieldSchema::new("port", ValueKind::Number).with_required(true),
+                    ]
+                    .into(),
+                ))
+                .with_mutability(Mutability::Module),
+        ])
+    }
+
+    /// 导出为 JSON Schema（draft 2020-12），每个变量对应一个属性
+    pub fn json_schema(&self) -> serde_json::Value {
+        let mut properties = serde_json::Map::new();
+        for (scope, var) in self.iter_all() {
+            let mut prop = var.json_schema();
+            // 以所在作用域为准，未经 `mark_vars_scope` 的定义 mutability 仍为默认值
+            if let Some(prop) = prop.as_object_mut() {
+                if scope == Mutability::Immutable {
+                    prop.insert("readOnly".into(), true.into());
+                } else {
+                    prop.remove("readOnly");
+                }
+            }
+            properties.insert(var.name().clone(), prop);
+        }
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": properties,
+        })
+    }
+
+    pub(crate) fn take_include(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.include)
+    }
     pub fn mark_vars_scope(&mut self) {
         for var in self.immutable_vars.iter_mut() {
             var.set_mutability(Mutability::Immutable);
@@ -81,27 +192,166 @@ impl VarCollection {
         }
         dict
     }
-    // 基于 VarDefinition 的 name 合并；当 `overwrite=true` 时后者覆盖前者
+    // 基于 VarDefinition 的 name（大小写不敏感）合并；当 `overwrite=true` 时后者覆盖前者
     pub fn merge(self, other: VarCollection) -> Self {
         let immutable_vars = merge_vec(self.immutable_vars, other.immutable_vars, false);
         let system_vars = merge_vec(self.system_vars, other.system_vars, true);
         let module_vars = merge_vec(self.module_vars, other.module_vars, true);
         Self {
+            include: Vec::new(),
             immutable_vars,
             system_vars,
             module_vars,
         }
     }
 
+    /// 找出 `other` 中与同一作用域内已有定义名称仅大小写不同的定义
+    ///
+    /// 已有定义先在本集合中查找，再在 `other` 中排在前面的定义中查找，
+    /// 因此 `other` 自身包含的 `foo`/`FOO` 同样报告。
+    /// 不同作用域的同名定义不算冲突：合并时各自保留，由 [`VarCollection::value_dict`]
+    /// 按 immutable、system、module 的顺序展开，后者覆盖前者。
+    pub fn case_collisions(&self, other: &VarCollection) -> Vec<CaseCollision> {
+        let mut found = Vec::new();
+        let others: Vec<_> = other.iter_all().collect();
+        for (i, (scope, incoming)) in others.iter().enumerate() {
+            let clash = |(s, v): &(Mutability, &VarDefinition)| {
+                s == scope && v.name() != incoming.name() && name_key(v) == name_key(incoming)
+            };
+            let existing = self
+                .iter_all()
+                .find(|x| clash(x))
+                .or_else(|| others[..i].iter().find(|x| clash(x)).cloned());
+            if let Some((_, existing)) = existing {
+                found.push(CaseCollision {
+                    existing: existing.name().clone(),
+                    incoming: incoming.name().clone(),
+                });
+            }
+        }
+        found
+    }
+
+    /// 同 `merge`，并以 warn 日志报告且返回大小写冲突
+    pub fn merge_case_checked(self, other: VarCollection) -> (Self, Vec<CaseCollision>) {
+        let collisions = self.case_collisions(&other);
+        for collision in &collisions {
+            log::warn!("{collision}");
+        }
+        (self.merge(other), collisions)
+    }
+
+    /// 存在大小写冲突时整体失败，返回 `VarsReason::Collision`
+    pub fn merge_case_strict(self, other: VarCollection) -> VarsResult<Self> {
+        let collisions = self.case_collisions(&other);
+        if !collisions.is_empty() {
+            let detail = collisions
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(StructError::from(VarsReason::Collision(detail)));
+        }
+        Ok(self.merge(other))
+    }
+
+    /// 按 `var.mutability()` 放入对应作用域，同名规则同 `merge`；以 warn 日志报告并返回大小写冲突
+    pub fn insert_case_checked(&mut self, var: VarDefinition) -> Option<CaseCollision> {
+        let include = std::mem::take(&mut self.include);
+        let (mut merged, collisions) =
+            std::mem::take(self).merge_case_checked(VarCollection::define(vec![var]));
+        merged.include = include;
+        *self = merged;
+        collisions.into_iter().next()
+    }
+
+    /// 同 `insert_case_checked`，存在大小写冲突时不插入并返回 `VarsReason::Collision`
+    pub fn insert_case_strict(&mut self, var: VarDefinition) -> VarsResult<()> {
+        let incoming = VarCollection::define(vec![var]);
+        if let Some(collision) = self.case_collisions(&incoming).into_iter().next() {
+            return Err(StructError::from(VarsReason::Collision(
+                collision.to_string(),
+            )));
+        }
+        let include = std::mem::take(&mut self.include);
+        let mut merged = std::mem::take(self).merge(incoming);
+        merged.include = include;
+        *self = merged;
+        Ok(())
+    }
+
+    /// 按各定义的 schema / items 校验 `dict` 中对应的值，没有定义的键忽略
+    pub fn validate_dict(&self, dict: &ValueDict) -> VarsResult<()> {
+        for (key, value) in dict.iter() {
+            if let Some(var) = self.find(key.as_str()) {
+                var.validate_value(key.as_str(), value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 为所有变量名加上 `namespace.` 前缀
+    pub fn with_namespace(self, namespace: &str) -> Self {
+        let prefix = |vars: Vec<VarDefinition>| {
+            vars.into_iter()
+                .map(|v| v.with_namespace(namespace))
+                .collect::<Vec<_>>()
+        };
+        Self {
+            include: self.include,
+            immutable_vars: prefix(self.immutable_vars),
+            system_vars: prefix(self.system_vars),
+            module_vars: prefix(self.module_vars),
+        }
+    }
+
+    /// 将 `other` 置于 `namespace` 下合并，避免不同模块的同名变量互相覆盖
+    pub fn merge_namespaced(self, namespace: &str, other: VarCollection) -> Self {
+        self.merge(other.with_namespace(namespace))
+    }
+
+    /// 按 `when` 条件筛选定义：丢弃不满足条件的定义，满足条件的定义覆盖同名的无条件定义
+    ///
+    /// `dict` 提供 `OS`/`ARCH` 及自定义标志，见 [`VarCondition`](super::VarCondition)。
+    pub fn resolve(self, dict: &EnvDict) -> Self {
+        Self {
+            include: self.include,
+            immutable_vars: resolve_vec(self.immutable_vars, dict),
+            system_vars: resolve_vec(self.system_vars, dict),
+            module_vars: resolve_vec(self.module_vars, dict),
+        }
+    }
+
     pub fn merge_system(self, other: VarCollection) -> Self {
         let system_vars = merge_vec(self.system_vars, other.system_vars, true);
         Self {
+            include: Vec::new(),
             immutable_vars: Vec::new(),
             system_vars,
             module_vars: Vec::new(),
         }
     }
 }
+// 先放入无条件定义，再用满足条件的定义覆盖同名项，保留首次出现的位置
+fn resolve_vec(vars: Vec<VarDefinition>, dict: &EnvDict) -> Vec<VarDefinition> {
+    let (conditional, plain): (Vec<_>, Vec<_>) = vars.into_iter().partition(|v| v.when().is_some());
+    let mut merged = IndexMap::new();
+    for var in plain {
+        merged.insert(name_key(&var).into_owned(), var);
+    }
+    for var in conditional {
+        if var.when().as_ref().is_some_and(|c| c.matches(dict)) {
+            merged.insert(name_key(&var).into_owned(), var);
+        }
+    }
+    merged.into_values().collect()
+}
+
+// 以大写名称为键，与 `ValueDict` 的键折叠一致
+fn name_key(var: &VarDefinition) -> std::borrow::Cow<'_, str> {
+    UpperKey::fold(var.name())
+}
+
 fn merge_vec(
     my: Vec<VarDefinition>,
     other: Vec<VarDefinition>,
@@ -110,12 +360,12 @@ fn merge_vec(
     let mut target = Vec::new();
     let mut merged = IndexMap::new();
     for var in my {
-        //immutable_vars.push(var)
-        merged.insert(var.name().clone(), var);
+        merged.insert(name_key(&var).into_owned(), var);
     }
     for var in other {
-        if is_over || !merged.contains_key(var.name()) {
-            merged.insert(var.name().clone(), var);
+        let key = name_key(&var).into_owned();
+        if is_over || !merged.contains_key(&key) {
+            merged.insert(key, var);
         }
     }
     for var in merged.into_values() {
@@ -126,7 +376,6 @@ fn merge_vec(
 
 #[cfg(test)]
 mod tests {
-    use crate::vars::ValueType;
     use crate::vars::definition::Mutability;
 
     use super::*;
@@ -358,4 +607,307 @@ mod tests {
         let json = serde_json::to_string(&default_collection).unwrap();
         assert_eq!(json, "{}");
     }
+
+    #[test]
+    fn test_merge_namespaced() {
+        let base = VarCollection::define(vec![
+            VarDefinition::from(("port", 80u64)).with_mutability(Mutability::Module),
+        ]);
+        let redis = VarCollection::define(vec![
+            VarDefinition::from(("port", 6379u64)).with_mutability(Mutability::Module),
+            VarDefinition::from(("host", "redis.local")).with_mutability(Mutability::System),
+        ]);
+
+        let merged = base.merge_namespaced("redis", redis);
+        let dict = merged.value_dict();
+        assert_eq!(dict.get("PORT"), Some(&ValueType::from(80u64)));
+        assert_eq!(dict.get("REDIS.PORT"), Some(&ValueType::from(6379u64)));
+        assert_eq!(
+            dict.get_scoped("redis", "host"),
+            Some(&ValueType::from("redis.local"))
+        );
+
+        // 重复加前缀不会叠加
+        let twice = merged.with_namespace("redis").with_namespace("redis");
+        assert!(twice.module_vars().iter().any(|v| v.name() == "redis.port"));
+    }
+
+    #[test]
+    fn test_iter_all() {
+        let yaml = r#"
+module:
+  - name: replicas
+    value: 3
+    description: pod replicas
+  - name: image
+    value: nginx
+immutable:
+  - name: env
+    value: prod
+    desc: deploy environment
+"#;
+        let collection: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        let items: Vec<(Mutability, &str, Option<&str>)> = collection
+            .iter_all()
+            .map(|(scope, var)| (scope, var.name().as_str(), var.desc().as_deref()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (Mutability::Immutable, "env", Some("deploy environment")),
+                (Mutability::Module, "replicas", Some("pod replicas")),
+                (Mutability::Module, "image", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collection_json_schema() {
+        let mut db = ValueObj::new();
+        db.insert("host".into(), ValueType::from("localhost"));
+        let collection = VarCollection::define(vec![
+            VarDefinition::from(("env", "prod"))
+                .with_desc(Some("deploy environment".into()))
+                .with_mutability(Mutability::Immutable),
+            VarDefinition::new("db", ValueType::Obj(db)).with_schema(Some(
+                vec![FieldSchema::new("host", ValueKind::String).with_required(true)].into(),
+            )),
+        ]);
+
+        let schema = collection.json_schema();
+        assert_eq!(schema["type"], "object");
+        let env = &schema["properties"]["env"];
+        assert_eq!(env["type"], "string");
+        assert_eq!(env["description"], "deploy environment");
+        assert_eq!(env["readOnly"], true);
+        let db = &schema["properties"]["db"];
+        assert_eq!(db["type"], "object");
+        assert_eq!(db["properties"]["host"]["type"], "string");
+        assert_eq!(db["required"][0], "host");
+    }
+
+    #[test]
+    fn test_json_schema_read_only_by_scope() {
+        let yaml = r#"
+immutable:
+  - name: env
+    value: prod
+module:
+  - name: workers
+    value: 4
+"#;
+        // 直接反序列化，不调用 mark_vars_scope
+        let collection: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        let schema = collection.json_schema();
+        assert_eq!(schema["properties"]["env"]["readOnly"], true);
+        assert!(schema["properties"]["workers"].get("readOnly").is_none());
+    }
+
+    #[test]
+    fn test_resolve_conditions() {
+        use crate::vars::VarCondition;
+
+        let yaml = r#"
+module:
+  - name: url
+    value: https://dl.example.com/linux-x86_64.tgz
+  - name: url
+    value: https://dl.example.com/darwin-arm64.tgz
+    when: { os: darwin, arch: arm64 }
+  - name: beta_only
+    value: true
+    when: { flags: { channel: beta } }
+"#;
+        let mut vars: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        vars.mark_vars_scope();
+        assert_eq!(
+            vars.module_vars()[1].when(),
+            &Some(
+                VarCondition::default()
+                    .with_os(Some("darwin".into()))
+                    .with_arch(Some("arm64".into()))
+            )
+        );
+
+        let mut mac = EnvDict::new();
+        mac.insert("OS", ValueType::from("macos"));
+        mac.insert("ARCH", ValueType::from("aarch64"));
+        mac.insert("CHANNEL", ValueType::from("beta"));
+        let resolved = vars.clone().resolve(&mac);
+        assert_eq!(resolved.module_vars().len(), 2);
+        let dict = resolved.value_dict();
+        assert_eq!(
+            dict.get("URL"),
+            Some(&ValueType::from("https://dl.example.com/darwin-arm64.tgz"))
+        );
+        assert_eq!(dict.get("BETA_ONLY"), Some(&ValueType::from(true)));
+
+        let mut linux = EnvDict::new();
+        linux.insert("OS", ValueType::from("linux"));
+        linux.insert("ARCH", ValueType::from("x86_64"));
+        let dict = vars.resolve(&linux).value_dict();
+        assert_eq!(
+            dict.get("URL"),
+            Some(&ValueType::from("https://dl.example.com/linux-x86_64.tgz"))
+        );
+        assert!(dict.get("BETA_ONLY").is_none());
+    }
+
+    #[test]
+    fn test_merge_case_collisions() {
+        let base = VarCollection::define(vec![
+            VarDefinition::from(("Region", "us")).with_mutability(Mutability::System),
+            VarDefinition::from(("env", "prod")).with_mutability(Mutability::Immutable),
+        ]);
+        let other = VarCollection::define(vec![
+            VarDefinition::from(("REGION", "eu")).with_mutability(Mutability::System),
+            VarDefinition::from(("ENV", "dev")).with_mutability(Mutability::Immutable),
+            VarDefinition::from(("env", "dev")).with_mutability(Mutability::Module),
+        ]);
+
+        let collisions = base.case_collisions(&other);
+        let pairs: Vec<_> = collisions
+            .iter()
+            .map(|c| (c.existing().as_str(), c.incoming().as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("env", "ENV"), ("Region", "REGION")]);
+
+        // 不同作用域的同名定义不算冲突；`other` 自身的大小写冲突同样报告
+        let modules = VarCollection::define(vec![
+            VarDefinition::from(("ENV", "dev")).with_mutability(Mutability::Module),
+            VarDefinition::from(("port", "80")).with_mutability(Mutability::Module),
+            VarDefinition::from(("Port", "81")).with_mutability(Mutability::Module),
+        ]);
+        let pairs: Vec<_> = base
+            .case_collisions(&modules)
+            .into_iter()
+            .map(|c| (c.existing().clone(), c.incoming().clone()))
+            .collect();
+        assert_eq!(pairs, vec![("port".to_string(), "Port".to_string())]);
+
+        let err = base.clone().merge_case_strict(other.clone()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`REGION` collides with existing `Region`")
+        );
+
+        // 名称折叠后合并：system 后者覆盖，immutable 保留先定义者
+        let (merged, collisions) = base.merge_case_checked(other);
+        assert_eq!(collisions.len(), 2);
+        assert_eq!(merged.system_vars().len(), 1);
+        assert_eq!(merged.immutable_vars().len(), 1);
+        assert_eq!(merged.immutable_vars()[0].name(), "env");
+        let dict = merged.value_dict();
+        assert_eq!(dict.get("REGION"), Some(&ValueType::from("eu")));
+    }
+
+    #[test]
+    fn test_insert_case_checked() {
+        let mut vars = VarCollection::define(vec![
+            VarDefinition::from(("straße", "a")).with_mutability(Mutability::System),
+        ]);
+        // 非 ASCII 名称按 `UpperKey` 的规则折叠：`straße` 与 `STRASSE` 冲突
+        let collision = vars
+            .insert_case_checked(
+                VarDefinition::from(("STRASSE", "b")).with_mutability(Mutability::System),
+            )
+            .unwrap();
+        assert_eq!(collision.existing(), "straße");
+        assert_eq!(vars.system_vars().len(), 1);
+        assert_eq!(
+            vars.value_dict().get("STRASSE"),
+            Some(&ValueType::from("b"))
+        );
+
+        assert!(
+            vars.insert_case_checked(VarDefinition::from(("other", "c")))
+                .is_none()
+        );
+        assert_eq!(vars.module_vars().len(), 1);
+
+        let err = vars
+            .insert_case_strict(VarDefinition::from(("OTHER", "d")))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`OTHER` collides with existing `other`")
+        );
+        assert_eq!(vars.value_dict().get("OTHER"), Some(&ValueType::from("c")));
+        vars.insert_case_strict(VarDefinition::from(("other", "e")))
+            .unwrap();
+        assert_eq!(vars.value_dict().get("OTHER"), Some(&ValueType::from("e")));
+    }
+
+    #[test]
+    fn test_validate_dict_items() {
+        let yaml = r#"
+module:
+  - name: retries
+    value: [1, 2, 3]
+    items: { type: number }
+  - name: hosts
+    value: [a.local]
+    items: { type: string, pattern: "[a-z.]+" }
+"#;
+        let vars: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        let mut dict = vars.value_dict();
+        assert!(vars.validate_dict(&dict).is_ok());
+
+        dict.insert(
+            "retries",
+            ValueType::List(vec![
+                ValueType::from(1u64),
+                ValueType::from(2u64),
+                ValueType::from("x"),
+            ]),
+        );
+        let err = vars.validate_dict(&dict).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("RETRIES[2]: expected Number, found String")
+        );
+
+        dict.insert("retries", ValueType::from(3u64));
+        let err = vars.validate_dict(&dict).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("RETRIES: expected List, found Number")
+        );
+
+        let bad = yaml.replace("value: [a.local]", "value: [A_LOCAL]");
+        let err = serde_yaml::from_str::<VarCollection>(&bad).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("hosts[0]: `A_LOCAL` does not match")
+        );
+    }
+
+    #[test]
+    fn test_validate_dict_items_non_ascii() {
+        let yaml = r#"
+module:
+  - name: straße
+    value: [a, b]
+    items: { type: string, pattern: "[a-z]+" }
+  - name: café
+    value: [1]
+    items: { type: number }
+"#;
+        let vars: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        let mut dict = vars.value_dict();
+        assert!(vars.validate_dict(&dict).is_ok());
+
+        // 字典键按 `UpperKey` 折叠为 `STRASSE`/`CAFÉ`，仍能找到对应定义并校验
+        dict.insert("straße", ValueType::List(vec![ValueType::from("B")]));
+        let err = vars.validate_dict(&dict).unwrap_err();
+        assert!(err.to_string().contains("STRASSE[0]: `B` does not match"));
+
+        dict.insert("straße", ValueType::List(vec![ValueType::from("b")]));
+        dict.insert("Café", ValueType::List(vec![ValueType::from("x")]));
+        let err = vars.validate_dict(&dict).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("CAFÉ[0]: expected Number, found String")
+        );
+    }
 }
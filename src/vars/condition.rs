@@ -0,0 +1,109 @@
+use getset::{Getters, WithSetters};
+use indexmap::IndexMap;
+use serde_derive::{Deserialize, Serialize};
+
+use super::EnvDict;
+
+// 统一常见的平台别名，使 `darwin`/`arm64` 与 Rust 的 `macos`/`aarch64` 等价
+fn normalize_platform(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    match name.as_str() {
+        "darwin" | "osx" => "macos".into(),
+        "win" | "win32" => "windows".into(),
+        "amd64" | "x64" => "x86_64".into(),
+        "arm64" => "aarch64".into(),
+        _ => name,
+    }
+}
+
+/// 变量定义的生效条件，全部条件满足时定义才生效
+///
+/// `os`/`arch` 优先取 `EnvDict` 中的 `OS`/`ARCH`，缺省为当前编译目标
+/// （`std::env::consts`）；`flags` 逐项与 `EnvDict` 中的值按字符串比较。
+///
+/// ```yaml
+/// when:
+///   os: darwin
+///   arch: arm64
+///   flags:
+///     channel: beta
+/// ```
+#[derive(Getters, WithSetters, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[getset(get = "pub", set_with = "pub")]
+#[serde(deny_unknown_fields)]
+pub struct VarCondition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    os: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arch: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    flags: IndexMap<String, String>,
+}
+
+impl VarCondition {
+    pub fn with_flag<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.flags.insert(key.into(), value.into());
+        self
+    }
+
+    fn fact(dict: &EnvDict, key: &str, default: &str) -> String {
+        let value = dict
+            .get_case_insensitive(key)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| default.to_string());
+        normalize_platform(&value)
+    }
+
+    pub fn matches(&self, dict: &EnvDict) -> bool {
+        if let Some(os) = &self.os
+            && normalize_platform(os) != Self::fact(dict, "OS", std::env::consts::OS)
+        {
+            return false;
+        }
+        if let Some(arch) = &self.arch
+            && normalize_platform(arch) != Self::fact(dict, "ARCH", std::env::consts::ARCH)
+        {
+            return false;
+        }
+        self.flags.iter().all(|(key, expect)| {
+            dict.get_case_insensitive(key)
+                .is_some_and(|v| v.to_string() == *expect)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueType;
+
+    #[test]
+    fn test_condition_matches() {
+        let mut dict = EnvDict::new();
+        dict.insert("OS", ValueType::from("darwin"));
+        dict.insert("ARCH", ValueType::from("arm64"));
+        dict.insert("CHANNEL", ValueType::from("beta"));
+
+        assert!(VarCondition::default().matches(&dict));
+        let mac_arm = VarCondition::default()
+            .with_os(Some("macos".into()))
+            .with_arch(Some("aarch64".into()));
+        assert!(mac_arm.matches(&dict));
+        assert!(
+            !mac_arm
+                .clone()
+                .with_arch(Some("x86_64".into()))
+                .matches(&dict)
+        );
+        assert!(mac_arm.clone().with_flag("channel", "beta").matches(&dict));
+        assert!(!mac_arm.with_flag("channel", "stable").matches(&dict));
+
+        let current = VarCondition::default().with_os(Some(std::env::consts::OS.into()));
+        assert!(current.matches(&EnvDict::new()));
+    }
+
+    #[test]
+    fn test_condition_rejects_unknown_fields() {
+        assert!(serde_yaml::from_str::<VarCondition>("{ os: linux, platform: x }").is_err());
+    }
+}
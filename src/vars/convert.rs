@@ -0,0 +1,259 @@
+use std::{net::IpAddr, time::Duration};
+
+use orion_error::StructError;
+
+use super::{
+    ValueDict, ValueType,
+    error::{VarsReason, VarsResult},
+};
+
+impl ValueType {
+    /// 转为 `u64`：整数；非负且无小数部分的浮点；可解析为整数的字符串
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            ValueType::Number(n) => Some(*n),
+            ValueType::Float(f) if *f >= 0.0 && f.fract() == 0.0 && *f < u64::MAX as f64 => {
+                Some(*f as u64)
+            }
+            ValueType::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 转为 `f64`：整数、浮点或可解析为数字的字符串
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ValueType::Number(n) => Some(*n as f64),
+            ValueType::Float(f) => Some(*f),
+            ValueType::String(s) => s.trim().parse().ok().filter(|f: &f64| f.is_finite()),
+            _ => None,
+        }
+    }
+
+    /// 转为 `bool`：布尔；整数 `0`/`1`；字符串 `true/false`、`yes/no`、`on/off`、`1/0`（大小写不敏感）
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueType::Bool(b) => Some(*b),
+            ValueType::Number(0) => Some(false),
+            ValueType::Number(1) => Some(true),
+            ValueType::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Some(true),
+                "false" | "no" | "off" | "0" => Some(false),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// 转为 `Duration`：数字按秒计；字符串为秒数或带单位的组合，如 `500ms`、`30s`、`5m`、`1h30m`、`2d`
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            ValueType::Number(n) => Some(Duration::from_secs(*n)),
+            ValueType::Float(f) => Duration::try_from_secs_f64(*f).ok(),
+            ValueType::String(s) => parse_duration(s.trim()),
+            _ => None,
+        }
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = s.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let mut total = Duration::ZERO;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let num_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let num: f64 = rest[..num_len].parse().ok()?;
+        rest = &rest[num_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit = match rest[..unit_len].trim() {
+            "ms" => 0.001,
+            "s" | "sec" => 1.0,
+            "m" | "min" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total += Duration::try_from_secs_f64(num * unit).ok()?;
+    }
+    Some(total)
+}
+
+/// 从 `ValueType` 按既定的转换规则取出具体类型，供 [`ValueDict::try_get_typed`] 使用
+pub trait FromValue: Sized {
+    /// 类型名，用于错误信息
+    const NAME: &'static str;
+    fn from_value(value: &ValueType) -> Option<Self>;
+}
+
+macro_rules! impl_from_value_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl FromValue for $ty {
+                const NAME: &'static str = stringify!($ty);
+                fn from_value(value: &ValueType) -> Option<Self> {
+                    value.as_u64().and_then(|n| <$ty>::try_from(n).ok())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_value_unsigned!(u8, u16, u32, u64, usize);
+
+impl FromValue for f64 {
+    const NAME: &'static str = "f64";
+    fn from_value(value: &ValueType) -> Option<Self> {
+        value.as_f64()
+    }
+}
+
+impl FromValue for bool {
+    const NAME: &'static str = "bool";
+    fn from_value(value: &ValueType) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+impl FromValue for Duration {
+    const NAME: &'static str = "Duration";
+    fn from_value(value: &ValueType) -> Option<Self> {
+        value.as_duration()
+    }
+}
+
+impl FromValue for IpAddr {
+    const NAME: &'static str = "IpAddr";
+    fn from_value(value: &ValueType) -> Option<Self> {
+        match value {
+            ValueType::Ip(ip) => Some(*ip),
+            ValueType::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for String {
+    const NAME: &'static str = "String";
+    fn from_value(value: &ValueType) -> Option<Self> {
+        match value {
+            ValueType::Obj(_) | ValueType::List(_) => None,
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+impl ValueDict {
+    /// 按类型读取值：键不存在为 `Ok(None)`，无法转换时返回 `VarsReason::Convert`
+    pub fn try_get_typed<T: FromValue>(&self, key: &str) -> VarsResult<Option<T>> {
+        let Some(value) = self.get_case_insensitive(key) else {
+            return Ok(None);
+        };
+        T::from_value(value).map(Some).ok_or_else(|| {
+            StructError::from(VarsReason::Convert(format!(
+                "{}: expected {}, found {} `{value}`",
+                key.to_uppercase(),
+                T::NAME,
+                value.variant_name()
+            )))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_coercion() {
+        assert_eq!(ValueType::from("42").as_u64(), Some(42));
+        assert_eq!(ValueType::from(3.0).as_u64(), Some(3));
+        assert_eq!(ValueType::from(3.5).as_u64(), None);
+        // `u64::MAX as f64` 为 2^64，超出 u64，不能饱和为 `u64::MAX`
+        assert_eq!(ValueType::Float(18446744073709551616.0).as_u64(), None);
+        assert_eq!(ValueType::from(true).as_u64(), None);
+
+        assert_eq!(ValueType::from("Yes").as_bool(), Some(true));
+        assert_eq!(ValueType::from("off").as_bool(), Some(false));
+        assert_eq!(ValueType::from(1u64).as_bool(), Some(true));
+        assert_eq!(ValueType::from(2u64).as_bool(), None);
+        assert_eq!(ValueType::from("maybe").as_bool(), None);
+
+        assert_eq!(ValueType::from("2.5").as_f64(), Some(2.5));
+        assert_eq!(ValueType::from("NaN").as_f64(), None);
+    }
+
+    #[test]
+    fn test_duration_coercion() {
+        assert_eq!(
+            ValueType::from(30u64).as_duration(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            ValueType::from(0.5).as_duration(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            ValueType::from("500ms").as_duration(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(
+            ValueType::from("1h30m").as_duration(),
+            Some(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            ValueType::from("2d").as_duration(),
+            Some(Duration::from_secs(172800))
+        );
+        assert_eq!(
+            ValueType::from("15").as_duration(),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(ValueType::from("5 weeks").as_duration(), None);
+        assert_eq!(ValueType::from("").as_duration(), None);
+        assert_eq!(ValueType::from("-1").as_duration(), None);
+    }
+
+    #[test]
+    fn test_try_get_typed() {
+        let mut dict = ValueDict::new();
+        dict.insert("port", ValueType::from("8080"));
+        dict.insert("debug", ValueType::from("on"));
+        dict.insert("timeout", ValueType::from("30s"));
+        dict.insert("host", ValueType::from("10.0.0.1"));
+        dict.insert("big", ValueType::from(70000u64));
+
+        assert_eq!(dict.try_get_typed::<u16>("port").unwrap(), Some(8080));
+        assert_eq!(dict.try_get_typed::<bool>("debug").unwrap(), Some(true));
+        assert_eq!(
+            dict.try_get_typed::<Duration>("timeout").unwrap(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            dict.try_get_typed::<IpAddr>("host").unwrap(),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(
+            dict.try_get_typed::<String>("port").unwrap(),
+            Some("8080".to_string())
+        );
+        assert_eq!(dict.try_get_typed::<u64>("missing").unwrap(), None);
+
+        let err = dict.try_get_typed::<u16>("big").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("BIG: expected u16, found Number `70000`")
+        );
+        let err = dict.try_get_typed::<bool>("timeout").unwrap_err();
+        assert!(err.to_string().contains("expected bool, found String"));
+    }
+}
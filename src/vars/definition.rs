@@ -1,7 +1,16 @@
 use getset::{Getters, Setters, WithSetters};
 use serde_derive::{Deserialize, Serialize};
 
-use super::ValueType;
+use super::{
+    UpperKey, ValueType,
+    condition::VarCondition,
+    constraint::ValueConstraint,
+    dict::namespaced_key,
+    error::{VarsReason, VarsResult},
+    location::SourceLocation,
+    schema::{ListSchema, ObjSchema, ValueKind},
+};
+use orion_error::StructError;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 pub enum Mutability {
@@ -59,8 +68,9 @@ impl Mutability {
 pub trait VarToValue<T> {
     fn to_val(&self) -> T;
 }
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Getters, WithSetters, Setters)]
+#[derive(Clone, Debug, Serialize, Deserialize, Getters, WithSetters, Setters)]
 #[getset(get = "pub")]
+#[serde(try_from = "VarDefinitionData")]
 pub struct VarDefinition {
     name: String,
     value: ValueType,
@@ -68,14 +78,267 @@ pub struct VarDefinition {
     #[serde(
         skip_serializing_if = "Option::is_none",
         rename = "desc",
-        alias = "desp"
+        alias = "desp",
+        alias = "description"
     )]
     desc: Option<String>,
+    /// 示例值，用于文档、JSON Schema 与交互提示
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    example: Option<ValueType>,
+    /// 废弃说明，存在即表示该变量已废弃
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deprecated: Option<String>,
+    /// 对象类型变量的字段声明，加载时据此校验 `value`
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema: Option<ObjSchema>,
+    /// 列表类型变量的元素声明，加载时据此校验 `value`
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    items: Option<ListSchema>,
+    /// 生效条件，不满足时该定义在 `VarCollection::resolve` 中被丢弃
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    when: Option<VarCondition>,
+    /// 取值约束，`scope` 限定数值范围（含两端），加载时据此校验 `value`
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    constraint: Option<ValueConstraint>,
+    /// 旧变量名，见 [`VarCollection::migrate`](super::VarCollection::migrate)
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    renamed_from: Vec<String>,
+    /// 加载自文件时定义所在位置
+    #[getset(get = "pub", set = "pub(crate)")]
+    #[serde(skip)]
+    source: Option<SourceLocation>,
     #[getset(get = "pub", set_with = "pub", set = "pub")]
     #[serde(default, skip)]
     mutability: Mutability,
 }
+
+// 来源位置不参与比较：同一定义从文件加载与在代码中构造应视为相等
+impl PartialEq for VarDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        let VarDefinition {
+            name,
+            value,
+            desc,
+            example,
+            deprecated,
+            schema,
+            items,
+            when,
+            constraint,
+            renamed_from,
+            source: _,
+            mutability,
+        } = self;
+        *name == other.name
+            && *value == other.value
+            && *desc == other.desc
+            && *example == other.example
+            && *deprecated == other.deprecated
+            && *schema == other.schema
+            && *items == other.items
+            && *when == other.when
+            && *constraint == other.constraint
+            && *renamed_from == other.renamed_from
+            && *mutability == other.mutability
+    }
+}
+
+/// `VarDefinition` 的反序列化中间形态，转换时执行 schema 校验
+#[derive(Deserialize)]
+struct VarDefinitionData {
+    name: String,
+    value: ValueType,
+    #[serde(default, rename = "desc", alias = "desp", alias = "description")]
+    desc: Option<String>,
+    #[serde(default)]
+    example: Option<ValueType>,
+    #[serde(default)]
+    deprecated: Option<String>,
+    #[serde(default)]
+    schema: Option<ObjSchema>,
+    #[serde(default)]
+    items: Option<ListSchema>,
+    #[serde(default)]
+    when: Option<VarCondition>,
+    #[serde(default)]
+    constraint: Option<ValueConstraint>,
+    #[serde(default)]
+    renamed_from: Vec<String>,
+}
+
+impl TryFrom<VarDefinitionData> for VarDefinition {
+    type Error = StructError<VarsReason>;
+    fn try_from(data: VarDefinitionData) -> Result<Self, Self::Error> {
+        let var = VarDefinition {
+            name: data.name,
+            value: data.value,
+            desc: data.desc,
+            example: data.example,
+            deprecated: data.deprecated,
+            schema: data.schema,
+            items: data.items,
+            when: data.when,
+            constraint: data.constraint,
+            renamed_from: data.renamed_from,
+            source: None,
+            mutability: Mutability::default(),
+        };
+        var.validate()?;
+        Ok(var)
+    }
+}
+
 impl VarDefinition {
+    pub fn new<S: Into<String>>(name: S, value: ValueType) -> Self {
+        VarDefinition {
+            name: name.into(),
+            value,
+            desc: None,
+            example: None,
+            deprecated: None,
+            schema: None,
+            items: None,
+            when: None,
+            constraint: None,
+            renamed_from: Vec::new(),
+            source: None,
+            mutability: Mutability::default(),
+        }
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated.is_some()
+    }
+
+    /// 名称或任一旧名称与 `name` 相同（按 `UpperKey` 折叠，大小写不敏感）
+    pub fn is_known_as(&self, name: &str) -> bool {
+        let name = UpperKey::fold(name);
+        std::iter::once(&self.name)
+            .chain(&self.renamed_from)
+            .any(|x| UpperKey::fold(x) == name)
+    }
+
+    /// 生成该变量的 JSON Schema 片段，`value` 作为 `default`
+    pub fn json_schema(&self) -> serde_json::Value {
+        let mut prop = serde_json::Map::new();
+        let kind = ValueKind::from(&self.value);
+        prop.insert("type".into(), kind.json_type().into());
+        if let ValueType::Ip(ip) = &self.value {
+            let format = if ip.is_ipv4() { "ipv4" } else { "ipv6" };
+            prop.insert("format".into(), format.into());
+        }
+        if let Some(desc) = &self.desc {
+            prop.insert("description".into(), desc.as_str().into());
+        }
+        prop.insert("default".into(), serde_json::json!(self.value));
+        if let Some(example) = &self.example {
+            prop.insert("examples".into(), serde_json::json!([example]));
+        }
+        if self.is_deprecated() {
+            prop.insert("deprecated".into(), true.into());
+        }
+        if !self.is_mutable() {
+            prop.insert("readOnly".into(), true.into());
+        }
+        if let Some(schema) = &self.schema {
+            let (properties, required) = schema.json_properties();
+            prop.insert("properties".into(), properties.into());
+            prop.insert("required".into(), required.into());
+            prop.insert("additionalProperties".into(), false.into());
+        }
+        if let Some(items) = &self.items {
+            prop.insert("items".into(), items.json_items());
+        }
+        if let Some(ValueConstraint::Scope(scope)) = &self.constraint {
+            prop.insert("minimum".into(), scope.beg.into());
+            prop.insert("maximum".into(), scope.end.into());
+        }
+        serde_json::Value::Object(prop)
+    }
+
+    /// 交互式输入时展示的提示文本
+    ///
+    /// 形如 `PORT - listen port [default: 8080] (e.g. 9090) (deprecated: use HTTP_PORT)`
+    pub fn prompt(&self) -> String {
+        let mut out = self.name.clone();
+        if let Some(desc) = &self.desc {
+            out.push_str(&format!(" - {desc}"));
+        }
+        out.push_str(&format!(" [default: {}]", self.value));
+        if let Some(example) = &self.example {
+            out.push_str(&format!(" (e.g. {example})"));
+        }
+        if let Some(note) = &self.deprecated {
+            out.push_str(&format!(" (deprecated: {note})"));
+        }
+        out
+    }
+
+    /// 为变量名加上命名空间前缀，已带该前缀时保持不变
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        let prefix = UpperKey::fold(&namespaced_key(namespace, "")).into_owned();
+        if !UpperKey::fold(&self.name).starts_with(&prefix) {
+            self.name = namespaced_key(namespace, &self.name);
+        }
+        self
+    }
+
+    /// 按 schema / items / constraint 校验默认值；均未声明时总是通过
+    pub fn validate(&self) -> VarsResult<()> {
+        self.validate_value(&self.name, &self.value)
+    }
+
+    /// 按本定义的 schema / items / constraint 校验任意值，`path` 用于错误信息定位
+    pub fn validate_value(&self, path: &str, value: &ValueType) -> VarsResult<()> {
+        if let Some(schema) = &self.schema {
+            match value {
+                ValueType::Obj(obj) => schema.validate(path, obj)?,
+                other => {
+                    return Err(StructError::from(VarsReason::Schema(format!(
+                        "{path}: expected Obj, found {}",
+                        other.variant_name()
+                    ))));
+                }
+            }
+        }
+        if let Some(items) = &self.items {
+            match value {
+                ValueType::List(list) => items.validate(path, list)?,
+                other => {
+                    return Err(StructError::from(VarsReason::Schema(format!(
+                        "{path}: expected List, found {}",
+                        other.variant_name()
+                    ))));
+                }
+            }
+        }
+        if let Some(ValueConstraint::Scope(scope)) = &self.constraint {
+            let in_scope = match value {
+                ValueType::Number(n) => (scope.beg..=scope.end).contains(n),
+                ValueType::Float(f) => *f >= scope.beg as f64 && *f <= scope.end as f64,
+                other => {
+                    return Err(StructError::from(VarsReason::Schema(format!(
+                        "{path}: expected Number, found {}",
+                        other.variant_name()
+                    ))));
+                }
+            };
+            if !in_scope {
+                return Err(StructError::from(VarsReason::Schema(format!(
+                    "{path}: {value} out of scope {}..={}",
+                    scope.beg, scope.end
+                ))));
+            }
+        }
+        Ok(())
+    }
     pub fn is_mutable(&self) -> bool {
         match self.mutability {
             Mutability::Immutable => false,
@@ -105,53 +368,28 @@ impl VarDefinition {
 }
 impl From<(&str, &str)> for VarDefinition {
     fn from(value: (&str, &str)) -> Self {
-        VarDefinition {
-            name: value.0.to_string(),
-            desc: None,
-            value: ValueType::from(value.1),
-            mutability: Mutability::default(),
-        }
+        VarDefinition::new(value.0, ValueType::from(value.1))
     }
 }
 impl From<(&str, bool)> for VarDefinition {
     fn from(value: (&str, bool)) -> Self {
-        VarDefinition {
-            name: value.0.to_string(),
-            desc: None,
-            value: ValueType::from(value.1),
-            mutability: Mutability::default(),
-        }
+        VarDefinition::new(value.0, ValueType::from(value.1))
     }
 }
 impl From<(&str, u64)> for VarDefinition {
     fn from(value: (&str, u64)) -> Self {
-        VarDefinition {
-            name: value.0.to_string(),
-            desc: None,
-            value: ValueType::from(value.1),
-            mutability: Mutability::default(),
-        }
+        VarDefinition::new(value.0, ValueType::from(value.1))
     }
 }
 impl From<(&str, f64)> for VarDefinition {
     fn from(value: (&str, f64)) -> Self {
-        VarDefinition {
-            name: value.0.to_string(),
-            desc: None,
-            value: ValueType::from(value.1),
-            mutability: Mutability::default(),
-        }
+        VarDefinition::new(value.0, ValueType::from(value.1))
     }
 }
 
 impl From<(&str, ValueType)> for VarDefinition {
     fn from(value: (&str, ValueType)) -> Self {
-        VarDefinition {
-            name: value.0.to_string(),
-            desc: None,
-            value: value.1,
-            mutability: Mutability::default(),
-        }
+        VarDefinition::new(value.0, value.1)
     }
 }
 
@@ -188,28 +426,16 @@ mod tests {
 
     #[test]
     fn test_var_definition_is_mutable() {
-        let immutable_var = VarDefinition {
-            name: "test".to_string(),
-            desc: None,
-            value: ValueType::from("value"),
-            mutability: Mutability::Immutable,
-        };
+        let immutable_var = VarDefinition::new("test", ValueType::from("value"))
+            .with_mutability(Mutability::Immutable);
         assert!(!immutable_var.is_mutable());
 
-        let public_var = VarDefinition {
-            name: "test".to_string(),
-            desc: None,
-            value: ValueType::from("value"),
-            mutability: Mutability::System,
-        };
+        let public_var = VarDefinition::new("test", ValueType::from("value"))
+            .with_mutability(Mutability::System);
         assert!(public_var.is_mutable());
 
-        let model_var = VarDefinition {
-            name: "test".to_string(),
-            desc: None,
-            value: ValueType::from("value"),
-            mutability: Mutability::Module,
-        };
+        let model_var = VarDefinition::new("test", ValueType::from("value"))
+            .with_mutability(Mutability::Module);
         assert!(model_var.is_mutable());
     }
 
@@ -238,26 +464,136 @@ mod tests {
 
     #[test]
     fn test_var_definition_serialization() {
-        let var = VarDefinition {
-            name: "test".to_string(),
-            desc: None,
-            value: ValueType::from("value"),
-            mutability: Mutability::System,
-        };
+        let var = VarDefinition::new("test", ValueType::from("value"))
+            .with_mutability(Mutability::System);
 
         // scope 应该被跳过序列化
         let json = serde_json::to_string(&var).unwrap();
         assert!(!json.contains("scope"));
 
         // Non-Default scope 应该被序列化
-        let var_immutable = VarDefinition {
-            name: "test".to_string(),
-            desc: None,
-            value: ValueType::from("value"),
-            mutability: Mutability::Immutable,
-        };
+        let var_immutable = VarDefinition::new("test", ValueType::from("value"))
+            .with_mutability(Mutability::Immutable);
 
         let json_immutable = serde_json::to_string(&var_immutable).unwrap();
         assert!(!json_immutable.contains("scope"));
     }
+
+    #[test]
+    fn test_var_definition_schema_validated_on_load() {
+        let yaml = r#"
+name: db
+value:
+  host: localhost
+  port: 5432
+schema:
+  - name: host
+    type: string
+    required: true
+  - name: port
+    type: number
+"#;
+        let var: VarDefinition = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(var.schema().as_ref().unwrap().fields().len(), 2);
+        assert!(var.validate().is_ok());
+
+        let bad = yaml.replace("port: 5432", "port: \"5432\"");
+        let err = serde_yaml::from_str::<VarDefinition>(&bad).unwrap_err();
+        assert!(err.to_string().contains("db.port: expected Number"));
+
+        let not_obj = r#"
+name: db
+value: localhost
+schema:
+  - name: host
+    type: string
+"#;
+        assert!(serde_yaml::from_str::<VarDefinition>(not_obj).is_err());
+    }
+
+    #[test]
+    fn test_var_definition_schema_roundtrip() {
+        use crate::vars::schema::{FieldSchema, ValueKind};
+        let mut obj = crate::vars::ValueObj::new();
+        obj.insert("host".into(), ValueType::from("localhost"));
+        let var = VarDefinition::new("db", ValueType::Obj(obj)).with_schema(Some(
+            vec![FieldSchema::new("host", ValueKind::String).with_required(true)].into(),
+        ));
+        let json = serde_json::to_string(&var).unwrap();
+        let loaded: VarDefinition = serde_json::from_str(&json).unwrap();
+        assert_eq!(var, loaded);
+    }
+
+    #[test]
+    fn test_var_definition_constraint() {
+        let yaml = r#"
+name: workers
+value: 4
+constraint: !scope { beg: 1, end: 16 }
+"#;
+        let var: VarDefinition = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(var.constraint(), &Some(ValueConstraint::scope(1, 16)));
+        assert!(
+            var.validate_value("WORKERS", &ValueType::from(16u64))
+                .is_ok()
+        );
+        let err = var
+            .validate_value("WORKERS", &ValueType::from(32u64))
+            .unwrap_err();
+        assert!(err.to_string().contains("WORKERS: 32 out of scope 1..=16"));
+        let schema = var.json_schema();
+        assert_eq!(
+            (&schema["minimum"], &schema["maximum"]),
+            (&1.into(), &16.into())
+        );
+
+        let json =
+            r#"{"name": "workers", "value": 4, "constraint": {"scope": {"beg": 1, "end": 16}}}"#;
+        assert_eq!(serde_json::from_str::<VarDefinition>(json).unwrap(), var);
+
+        let bad = yaml.replace("value: 4", "value: 0");
+        assert!(serde_yaml::from_str::<VarDefinition>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_with_namespace_case_insensitive() {
+        let var = VarDefinition::from(("REDIS.port", 6379u64)).with_namespace("redis");
+        assert_eq!(var.name(), "REDIS.port");
+        let var = VarDefinition::from(("port", 6379u64)).with_namespace("redis");
+        assert_eq!(var.name(), "redis.port");
+    }
+
+    #[test]
+    fn test_var_definition_doc_metadata() {
+        let yaml = r#"
+name: port
+value: 8080
+desc: listen port
+example: 9090
+deprecated: use HTTP_PORT
+"#;
+        let var: VarDefinition = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(var.example(), &Some(ValueType::from(9090u64)));
+        assert!(var.is_deprecated());
+        assert_eq!(
+            var.prompt(),
+            "port - listen port [default: 8080] (e.g. 9090) (deprecated: use HTTP_PORT)"
+        );
+
+        let schema = var.json_schema();
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["description"], "listen port");
+        assert_eq!(schema["default"], 8080);
+        assert_eq!(schema["examples"][0], 9090);
+        assert_eq!(schema["deprecated"], true);
+
+        let json = serde_json::to_string(&var).unwrap();
+        assert_eq!(serde_json::from_str::<VarDefinition>(&json).unwrap(), var);
+
+        let plain = VarDefinition::from(("host", "localhost"));
+        let json = serde_json::to_string(&plain).unwrap();
+        assert!(!json.contains("example"));
+        assert!(!json.contains("deprecated"));
+        assert_eq!(plain.prompt(), "host [default: localhost]");
+    }
 }
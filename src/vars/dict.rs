@@ -1,3 +1,8 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
 use derive_getters::Getters;
@@ -7,13 +12,35 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::vars::UpperKey;
 
-use super::{
-    EnvDict,
-    types::{EnvEvaluable, ValueType},
-};
+use super::types::{ValueHasher, ValueType};
+#[cfg(feature = "std")]
+use super::{EnvDict, global::system_facts, types::EnvEvaluable};
+
+pub type ValueMap = IndexMap<UpperKey, ValueType, ValueHasher>;
+
+/// 命名空间分隔符，带命名空间的键形如 `module.key`
+pub const NAMESPACE_SEP: char = '.';
+
+/// 导出时对 `module.key` 形式键的展开方式
+///
+/// 命名空间取键中第一个 `.` 之前的部分。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FlattenKeys {
+    /// 保持 `MODULE.KEY` 不变
+    #[default]
+    Keep,
+    /// 用指定字符替换所有 `.`，如 `MODULE_KEY`
+    Join(char),
+    /// 去掉命名空间只保留 `KEY`，同名时先出现者优先
+    Strip,
+}
 
-pub type ValueMap = IndexMap<UpperKey, ValueType>;
+/// 拼接命名空间与键名
+pub fn namespaced_key(namespace: &str, key: &str) -> String {
+    format!("{namespace}{NAMESPACE_SEP}{key}")
+}
 
+#[cfg(feature = "std")]
 impl EnvEvaluable<ValueMap> for ValueMap {
     fn env_eval(self, dict: &EnvDict) -> ValueMap {
         let mut cur_dict = dict.clone();
@@ -29,6 +56,7 @@ impl EnvEvaluable<ValueMap> for ValueMap {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvEvaluable<ValueDict> for ValueDict {
     fn env_eval(mut self, dict: &EnvDict) -> ValueDict {
         self.dict = self.dict.env_eval(dict);
@@ -41,6 +69,7 @@ impl EnvEvaluable<ValueDict> for ValueDict {
 pub struct ValueDict {
     dict: ValueMap,
 }
+#[cfg(feature = "std")]
 impl From<HashMap<String, String>> for ValueDict {
     fn from(map: HashMap<String, String>) -> Self {
         let mut vmap = ValueMap::new();
@@ -53,7 +82,7 @@ impl From<HashMap<String, String>> for ValueDict {
 impl ValueDict {
     pub fn new() -> Self {
         Self {
-            dict: ValueMap::new(),
+            dict: ValueMap::default(),
         }
     }
 
@@ -68,6 +97,27 @@ impl ValueDict {
         }
     }
 
+    /// 返回键按字典序排列的副本，嵌套对象同样排序
+    ///
+    /// `ValueDict` 默认保持插入顺序，序列化输出与合并/插入顺序一致；
+    /// 需要稳定 diff 的导出（如写入 YAML）先调用本方法。
+    pub fn sorted(&self) -> ValueDict {
+        let mut dict: ValueMap = self.iter().map(|(k, v)| (k.clone(), v.sorted())).collect();
+        dict.sort_unstable_keys();
+        Self { dict }
+    }
+
+    /// 注入标准化的系统信息，已存在的键保持不变
+    ///
+    /// 键：`OS`、`ARCH`、`OS_FAMILY`（`std::env::consts`），`OS_DISTRO`、`OS_VERSION`、
+    /// `OS_SYS`（同 `GXL_OS_SYS`），`NUM_CPUS`，`TMP_DIR`，以及可获取时的
+    /// `HOSTNAME`、`HOME`、`USER`。
+    #[cfg(feature = "std")]
+    pub fn with_system_facts(mut self) -> Self {
+        self.merge(&system_facts());
+        self
+    }
+
     /// 以大小写不敏感的方式获取值
     ///
     /// # 参数
@@ -89,14 +139,39 @@ impl ValueDict {
     /// assert_eq!(dict.get_case_insensitive("nonexistent"), None);
     /// ```
     pub fn get_case_insensitive<S: AsRef<str>>(&self, key: S) -> Option<&ValueType> {
-        let upper_key = UpperKey::from(key.as_ref());
-        self.dict.get(&upper_key)
+        self.dict.get(UpperKey::fold(key.as_ref()).as_ref())
     }
 
     #[deprecated(note = "renamed to get_case_insensitive()")]
     pub fn ucase_get<S: AsRef<str>>(&self, key: S) -> Option<&ValueType> {
         self.get_case_insensitive(key)
     }
+
+    /// 先查找 `namespace.key`，不存在时回退到 `key`
+    pub fn get_scoped<S: AsRef<str>>(&self, namespace: &str, key: S) -> Option<&ValueType> {
+        self.get_case_insensitive(namespaced_key(namespace, key.as_ref()))
+            .or_else(|| self.get_case_insensitive(key))
+    }
+
+    /// 按 `FlattenKeys` 展开带命名空间的键，供只接受扁平键的下游使用
+    pub fn flatten(&self, style: FlattenKeys) -> ValueDict {
+        let mut dict = ValueDict::new();
+        for (k, v) in self.dict.iter() {
+            let key = match style {
+                FlattenKeys::Keep => k.as_str().to_string(),
+                FlattenKeys::Join(sep) => k.as_str().replace(NAMESPACE_SEP, &sep.to_string()),
+                FlattenKeys::Strip => match k.as_str().split_once(NAMESPACE_SEP) {
+                    Some((_, key)) => key.to_string(),
+                    None => k.as_str().to_string(),
+                },
+            };
+            // 分隔符可能是小写字母，按 `UpperKey` 折叠后再判断先到者
+            if dict.get_case_insensitive(&key).is_none() {
+                dict.insert(key, v.clone());
+            }
+        }
+        dict
+    }
 }
 
 #[cfg(test)]
@@ -449,4 +524,81 @@ SPECIAL_CHARS: "Contains special characters:\n- Tabs:\t\n- Quotes: \"hello\"\n-
 
         println!("往返序列化测试通过！块数据格式在序列化/反序列化过程中保持正确。");
     }
+
+    #[test]
+    fn test_sorted() {
+        use crate::vars::ValueObj;
+
+        let mut inner = ValueObj::new();
+        inner.insert("zeta".into(), ValueType::from(1u64));
+        inner.insert("alpha".into(), ValueType::from(2u64));
+        let mut dict = ValueDict::new();
+        dict.insert("b_key", ValueType::from("b"));
+        dict.insert("a_key", ValueType::Obj(inner.clone()));
+        dict.insert("c_key", ValueType::List(vec![ValueType::Obj(inner)]));
+
+        // 默认保持插入顺序
+        let yaml = serde_yaml::to_string(&dict).unwrap();
+        assert!(yaml.find("B_KEY").unwrap() < yaml.find("A_KEY").unwrap());
+
+        let sorted = dict.sorted();
+        assert_eq!(sorted, dict);
+        let keys: Vec<_> = sorted.keys().map(|k| k.as_str().to_string()).collect();
+        assert_eq!(keys, vec!["A_KEY", "B_KEY", "C_KEY"]);
+        let yaml = serde_yaml::to_string(&sorted).unwrap();
+        assert_eq!(
+            yaml,
+            "A_KEY:\n  alpha: 2\n  zeta: 1\nB_KEY: b\nC_KEY:\n- alpha: 2\n  zeta: 1\n"
+        );
+    }
+
+    #[test]
+    fn test_get_scoped() {
+        let mut dict = ValueDict::new();
+        dict.insert("redis.port", ValueType::from(6379u64));
+        dict.insert("port", ValueType::from(80u64));
+        dict.insert("host", ValueType::from("localhost"));
+
+        assert_eq!(
+            dict.get_scoped("redis", "port"),
+            Some(&ValueType::from(6379u64))
+        );
+        assert_eq!(
+            dict.get_scoped("mysql", "port"),
+            Some(&ValueType::from(80u64))
+        );
+        assert_eq!(
+            dict.get_scoped("redis", "HOST"),
+            Some(&ValueType::from("localhost"))
+        );
+        assert_eq!(dict.get_scoped("redis", "user"), None);
+    }
+
+    #[test]
+    fn test_flatten_namespaced_keys() {
+        let mut dict = ValueDict::new();
+        dict.insert("redis.port", ValueType::from(6379u64));
+        dict.insert("mysql.port", ValueType::from(3306u64));
+        dict.insert("host", ValueType::from("localhost"));
+
+        let kept = dict.flatten(FlattenKeys::Keep);
+        assert_eq!(kept, dict);
+
+        let joined = dict.flatten(FlattenKeys::Join('_'));
+        assert_eq!(joined.get("REDIS_PORT"), Some(&ValueType::from(6379u64)));
+        assert_eq!(joined.get("MYSQL_PORT"), Some(&ValueType::from(3306u64)));
+        assert_eq!(joined.get("HOST"), Some(&ValueType::from("localhost")));
+
+        let stripped = dict.flatten(FlattenKeys::Strip);
+        assert_eq!(stripped.len(), 2);
+        assert_eq!(stripped.get("PORT"), Some(&ValueType::from(6379u64)));
+
+        // `AxB` 与 `AXB` 折叠后相同，保留先出现的
+        let mut dict = ValueDict::new();
+        dict.insert("axb", ValueType::from("first"));
+        dict.insert("a.b", ValueType::from("second"));
+        let joined = dict.flatten(FlattenKeys::Join('x'));
+        assert_eq!(joined.len(), 1);
+        assert_eq!(joined.get("AXB"), Some(&ValueType::from("first")));
+    }
 }
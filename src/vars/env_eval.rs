@@ -2,71 +2,80 @@ use std::env;
 
 use winnow::{Parser, token::take_until};
 
-use super::EnvDict;
+use super::{
+    EnvDict,
+    error::VarsResult,
+    expr::{eval_expr, expr_var_names},
+    global_vars::GlobalVars,
+    secret::find_secret_resolver,
+};
 
 fn until_beg<'i>(s: &mut &'i str) -> winnow::Result<&'i str> {
     let data = take_until(0.., "${").parse_next(s)?;
     "${".parse_next(s)?;
     Ok(data)
 }
-fn until_name_default<'i>(s: &mut &'i str) -> winnow::Result<Vec<&'i str>> {
-    // First, get everything until the closing '}'
-    let content = take_until(0.., "}").parse_next(s)?;
-    "}".parse_next(s)?;
-
-    // Check if there's a ':' in the content for default value syntax
-    if let Some(colon_pos) = content.find(':') {
-        // Split into variable name and default value
-        let var_name = &content[..colon_pos];
-        let default_value = &content[colon_pos + 1..];
-        Ok(vec![var_name, default_value])
+
+// 取到匹配的 `}` 为止；`${= ...}` 表达式中引号内的 `}` 不作为结束符
+fn until_end<'i>(s: &mut &'i str) -> Option<&'i str> {
+    let end = if s.starts_with('=') {
+        let mut quote = None;
+        s.char_indices().find_map(|(i, c)| {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '\'' || c == '"' => quote = Some(c),
+                None if c == '}' => return Some(i),
+                None => {}
+            }
+            None
+        })?
     } else {
-        // No default value, just the variable name
-        Ok(vec![content])
-    }
+        s.find('}')?
+    };
+    let content = &s[..end];
+    *s = &s[end + 1..];
+    Some(content)
 }
 
 /// Extracts all environment variable names from a string
-/// For `${VAR:default}` syntax, only returns "VAR"
+/// For `${VAR:default}` syntax, only returns "VAR";
+/// for `${= expr}` returns the variables referenced by the expression
 pub fn extract_env_var_names(input: &str) -> Vec<String> {
     let mut vars = Vec::new();
-    let mut chars = input.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '$' && chars.peek() == Some(&'{') {
-            chars.next(); // consume '{'
-
-            let mut var_name = String::new();
-            let mut found_end = false;
-
-            // Extract variable name until ':' or '}'
-            while let Some(&next_char) = chars.peek() {
-                if next_char == ':' || next_char == '}' {
-                    found_end = true;
-                    break;
-                }
-                var_name.push(next_char);
-                chars.next();
-            }
-
-            if found_end && !var_name.is_empty() {
-                vars.push(var_name);
-            }
-
-            // Skip to the closing '}'
-            while let Some(&next_char) = chars.peek() {
-                chars.next();
-                if next_char == '}' {
-                    break;
-                }
-            }
+    let mut data = input;
+    while until_beg.parse_next(&mut data).is_ok() {
+        let Some(content) = until_end(&mut data) else {
+            break;
+        };
+        if let Some(expr) = content.strip_prefix('=') {
+            vars.extend(expr_var_names(expr));
+            continue;
+        }
+        let name = match content.split_once(':') {
+            Some((scheme, _)) if find_secret_resolver(scheme).is_some() => continue,
+            Some((name, _)) => name,
+            None => content,
+        };
+        if !name.is_empty() {
+            vars.push(name.to_string());
         }
     }
-
     vars
 }
 
-pub fn expand_env_vars(dict: &EnvDict, input: &str) -> String {
+// 查找顺序：EnvDict → GlobalVars → 进程环境变量
+fn lookup(dict: &EnvDict, name: &str) -> Option<String> {
+    if let Some(found) = dict.get(name) {
+        Some(found.to_string())
+    } else if let Some(found) = GlobalVars::get(name) {
+        Some(found.to_string())
+    } else {
+        env::var(name).ok()
+    }
+}
+
+fn expand_impl(dict: &EnvDict, input: &str, strict: bool) -> VarsResult<String> {
     let mut out = String::new();
     let mut data = input;
     while !data.is_empty() {
@@ -76,41 +85,56 @@ pub fn expand_env_vars(dict: &EnvDict, input: &str) -> String {
             }
             Err(_e) => {
                 out.push_str(data);
-                return out;
+                return Ok(out);
             }
         }
-        match until_name_default.parse_next(&mut data) {
-            Ok(vecs) => match vecs.len() {
-                1 => {
-                    if let Some(found) = dict.get(vecs[0]) {
-                        out.push_str(found.to_string().as_str());
-                    } else if let Ok(found) = env::var(vecs[0]) {
-                        out.push_str(found.as_str());
-                    } else {
-                        out.push_str(format!("${{{}}}", vecs[0]).as_str());
-                    }
-                }
-                2 => {
-                    if let Some(found) = dict.get(vecs[0]) {
-                        out.push_str(found.to_string().as_str());
-                    } else if let Ok(found) = env::var(vecs[0]) {
-                        out.push_str(found.as_str());
-                    } else {
-                        out.push_str(vecs[1]);
-                    }
-                }
-                _ => {
-                    panic!()
+        let Some(content) = until_end(&mut data) else {
+            out.push_str("${");
+            out.push_str(data);
+            return Ok(out);
+        };
+        if let Some(expr) = content.strip_prefix('=') {
+            match eval_expr(dict, expr) {
+                Ok(value) => out.push_str(value.as_str()),
+                Err(e) if strict => return Err(e),
+                Err(_) => out.push_str(format!("${{{content}}}").as_str()),
+            }
+            continue;
+        }
+        match content.split_once(':') {
+            Some((scheme, reference)) if let Some(resolver) = find_secret_resolver(scheme) => {
+                match resolver.resolve(reference) {
+                    Ok(secret) => out.push_str(secret.as_str()),
+                    Err(e) if strict => return Err(e),
+                    Err(_) => out.push_str(format!("${{{content}}}").as_str()),
                 }
-            },
-            Err(_) => {
-                out.push_str("${");
-                out.push_str(data);
-                return out;
             }
+            Some((name, default)) => {
+                out.push_str(lookup(dict, name).as_deref().unwrap_or(default));
+            }
+            None => match lookup(dict, content) {
+                Some(found) => out.push_str(found.as_str()),
+                None => out.push_str(format!("${{{content}}}").as_str()),
+            },
         }
     }
-    out
+    Ok(out)
+}
+
+/// 展开 `${VAR}`、`${VAR:default}`、`${scheme:reference}` 与 `${= 表达式}`
+///
+/// `scheme` 为已注册的 [`SecretResolver`](super::SecretResolver) 时按密钥解析。
+/// 只有以 `=` 开头的内容按表达式计算，其余内容一律按变量名查找。
+/// 未定义的变量、无法计算的表达式和解析失败的密钥保持原样。
+pub fn expand_env_vars(dict: &EnvDict, input: &str) -> String {
+    expand_impl(dict, input, false).unwrap_or_else(|_| input.to_string())
+}
+
+/// 与 [`expand_env_vars`] 相同，但表达式或密钥解析出错时返回错误
+///
+/// 如 `${= ENV == 'prod' ? 5 : 1}`；表达式语法见 [`eval_expr`]。
+pub fn try_expand_env_vars(dict: &EnvDict, input: &str) -> VarsResult<String> {
+    expand_impl(dict, input, true)
 }
 
 #[cfg(test)]
@@ -326,4 +350,62 @@ mod tests {
             "database_url: postgresql://localhost/mydb, api_key: secret-key-123"
         );
     }
+
+    #[test]
+    fn test_expression_expansion() {
+        use super::{extract_env_var_names, try_expand_env_vars};
+
+        let mut dict = EnvDict::new();
+        dict.insert("ENV", ValueType::from("prod"));
+        dict.insert("NAME", ValueType::from("app"));
+        dict.insert("VERSION", ValueType::from("1.2"));
+        assert_eq!(
+            expand_env_vars(&dict, "replicas: ${= ENV == 'prod' ? 5 : 1}"),
+            "replicas: 5"
+        );
+        assert_eq!(
+            expand_env_vars(&dict, "${= NAME + ':' + VERSION}"),
+            "app:1.2"
+        );
+        // 表达式出错时宽松模式保持原样，严格模式返回错误
+        assert_eq!(expand_env_vars(&dict, "x${= NAME * 2}"), "x${= NAME * 2}");
+        let err = try_expand_env_vars(&dict, "x${= NAME * 2}").unwrap_err();
+        assert!(err.to_string().contains("operands must be numbers"));
+        assert_eq!(
+            try_expand_env_vars(&dict, "${UNDEFINED_EXPR_VAR}").unwrap(),
+            "${UNDEFINED_EXPR_VAR}"
+        );
+
+        let vars = extract_env_var_names("${= ENV == 'prod' ? PROD_REPLICAS : 1}-${NAME}");
+        assert_eq!(vars, vec!["ENV", "PROD_REPLICAS", "NAME"]);
+
+        // 字符串字面量中的 `}` 不会提前结束表达式
+        assert_eq!(expand_env_vars(&dict, "${= NAME + '}'}!"), "app}!");
+        assert_eq!(
+            extract_env_var_names("${= ENV == '}' ? A : B}"),
+            vec!["ENV", "A", "B"]
+        );
+    }
+
+    #[test]
+    fn test_non_expression_stays_literal() {
+        use super::{extract_env_var_names, try_expand_env_vars};
+
+        let dict = EnvDict::new();
+        // 没有 `=` 前缀的内容不会被当作表达式
+        assert_eq!(
+            try_expand_env_vars(&dict, "${PREFIX/suffix}").unwrap(),
+            "${PREFIX/suffix}"
+        );
+        assert_eq!(
+            try_expand_env_vars(&dict, "${foo bar}").unwrap(),
+            "${foo bar}"
+        );
+        assert_eq!(extract_env_var_names("${foo bar}"), vec!["foo bar"]);
+        // 带 `-` 的变量名按变量查找
+        let mut dict = EnvDict::new();
+        dict.insert("APP-NAME", ValueType::from("demo"));
+        assert_eq!(expand_env_vars(&dict, "${APP-NAME}"), "demo");
+        assert_eq!(extract_env_var_names("${APP-NAME}"), vec!["APP-NAME"]);
+    }
 }
@@ -8,6 +8,30 @@ pub enum VarsReason {
     UnKnow,
     #[error("format")]
     Format,
+    #[error("schema: {0}")]
+    #[from(skip)]
+    Schema(String),
+    #[error("include: {0}")]
+    #[from(skip)]
+    Include(String),
+    #[error("immutable: {0}")]
+    #[from(skip)]
+    Immutable(String),
+    #[error("expr: {0}")]
+    #[from(skip)]
+    Expr(String),
+    #[error("secret: {0}")]
+    #[from(skip)]
+    Secret(String),
+    #[error("override: {0}")]
+    #[from(skip)]
+    Override(String),
+    #[error("convert: {0}")]
+    #[from(skip)]
+    Convert(String),
+    #[error("collision: {0}")]
+    #[from(skip)]
+    Collision(String),
     #[error("{0}")]
     Uvs(UvsReason),
 }
@@ -17,6 +41,14 @@ impl ErrorCode for VarsReason {
         match self {
             VarsReason::Format => 501,
             VarsReason::UnKnow => 502,
+            VarsReason::Schema(_) => 503,
+            VarsReason::Include(_) => 504,
+            VarsReason::Immutable(_) => 505,
+            VarsReason::Expr(_) => 506,
+            VarsReason::Secret(_) => 507,
+            VarsReason::Override(_) => 508,
+            VarsReason::Convert(_) => 509,
+            VarsReason::Collision(_) => 510,
             VarsReason::Uvs(r) => r.error_code(),
         }
     }
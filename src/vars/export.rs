@@ -0,0 +1,266 @@
+use std::{collections::HashMap, process::Command};
+
+use getset::{Getters, WithSetters};
+use orion_error::StructError;
+
+use super::{
+    ValueDict, ValueType,
+    error::{VarsReason, VarsResult},
+};
+
+const REDACTED: &str = "******";
+
+/// 导出为环境变量的规则
+///
+/// - 键：加上 `prefix` 后，非 `[A-Z0-9_]` 字符替换为 `_`（如 `APP.PORT` → `APP_PORT`），
+///   数字开头时补 `_`
+/// - 值：标量取字符串形式，对象与列表序列化为 JSON
+/// - 环境变量名包含 `secret_patterns` 中任一片段（大小写不敏感）视为密钥，
+///   因此 `api.key` 与 `API_KEY` 同样匹配：`exclude_secrets` 为真时不导出，
+///   [`EnvExport::redacted_pairs`] 中显示为 `******`
+/// - 多个键映射到同一环境变量名（如 `APP.PORT` 与 `APP_PORT`）时保留先出现者
+#[derive(Getters, WithSetters, Clone, Debug)]
+#[getset(get = "pub", set_with = "pub")]
+pub struct EnvExport {
+    prefix: String,
+    secret_patterns: Vec<String>,
+    exclude_secrets: bool,
+}
+
+impl Default for EnvExport {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            secret_patterns: [
+                "PASSWORD",
+                "PASSWD",
+                "SECRET",
+                "TOKEN",
+                "PRIVATE_KEY",
+                "API_KEY",
+            ]
+            .map(String::from)
+            .to_vec(),
+            exclude_secrets: false,
+        }
+    }
+}
+
+impl EnvExport {
+    pub fn env_key(&self, key: &str) -> String {
+        let mut out: String = format!("{}{key}", self.prefix)
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if out.starts_with(|c: char| c.is_ascii_digit()) {
+            out.insert(0, '_');
+        }
+        out
+    }
+
+    pub fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_ascii_uppercase();
+        self.secret_patterns
+            .iter()
+            .any(|p| key.contains(&p.to_ascii_uppercase()))
+    }
+
+    fn env_value(value: &ValueType) -> String {
+        match value {
+            ValueType::Obj(_) | ValueType::List(_) => {
+                serde_json::to_string(value).unwrap_or_else(|_| value.to_string())
+            }
+            other => other.to_string(),
+        }
+    }
+
+    fn pairs_impl(&self, dict: &ValueDict, strict: bool) -> VarsResult<Vec<(String, String)>> {
+        let mut pairs = Vec::new();
+        let mut sources: HashMap<String, &str> = HashMap::new();
+        for (k, v) in dict.iter() {
+            let name = self.env_key(k.as_str());
+            if self.exclude_secrets && self.is_secret(&name) {
+                continue;
+            }
+            if let Some(first) = sources.get(&name) {
+                let msg = format!("`{first}` and `{}` both export as `{name}`", k.as_str());
+                if strict {
+                    return Err(StructError::from(VarsReason::Collision(msg)));
+                }
+                log::warn!("{msg}, keeping `{first}`");
+                continue;
+            }
+            sources.insert(name.clone(), k.as_str());
+            pairs.push((name, Self::env_value(v)));
+        }
+        Ok(pairs)
+    }
+
+    /// 按规则生成的 `(环境变量名, 值)`，保持字典顺序
+    ///
+    /// 环境变量名冲突时保留先出现者并以 warn 日志报告。
+    pub fn env_pairs(&self, dict: &ValueDict) -> Vec<(String, String)> {
+        self.pairs_impl(dict, false).unwrap_or_default()
+    }
+
+    /// 同 [`EnvExport::env_pairs`]，环境变量名冲突时返回 `VarsReason::Collision`
+    pub fn try_env_pairs(&self, dict: &ValueDict) -> VarsResult<Vec<(String, String)>> {
+        self.pairs_impl(dict, true)
+    }
+
+    /// 同 [`EnvExport::env_pairs`]，密钥值替换为 `******`，用于日志输出
+    pub fn redacted_pairs(&self, dict: &ValueDict) -> Vec<(String, String)> {
+        self.env_pairs(dict)
+            .into_iter()
+            .map(|(k, v)| {
+                if self.is_secret(&k) {
+                    (k, REDACTED.to_string())
+                } else {
+                    (k, v)
+                }
+            })
+            .collect()
+    }
+}
+
+impl ValueDict {
+    /// 按默认规则写入当前进程的环境变量，见 [`EnvExport`]
+    ///
+    /// # Safety
+    ///
+    /// 内部调用 [`std::env::set_var`]，要求与之相同：调用期间不能有其他线程读写进程环境变量。
+    /// 只应在启动阶段、创建其他线程之前调用；其余场景用 [`ValueDict::envs_for_command`]。
+    pub unsafe fn apply_to_env(&self) {
+        // SAFETY: 由调用方保证，见上文
+        unsafe { self.apply_to_env_with(&EnvExport::default()) };
+    }
+
+    /// 同 [`ValueDict::apply_to_env`]，使用指定规则
+    ///
+    /// # Safety
+    ///
+    /// 同 [`ValueDict::apply_to_env`]。
+    pub unsafe fn apply_to_env_with(&self, rule: &EnvExport) {
+        for (k, v) in rule.env_pairs(self) {
+            // SAFETY: 由调用方保证没有其他线程同时访问环境变量
+            unsafe { std::env::set_var(k, v) };
+        }
+    }
+
+    /// 按默认规则为子进程设置环境变量，不影响当前进程
+    pub fn envs_for_command<'a>(&self, cmd: &'a mut Command) -> &'a mut Command {
+        self.envs_for_command_with(cmd, &EnvExport::default())
+    }
+
+    pub fn envs_for_command_with<'a>(
+        &self,
+        cmd: &'a mut Command,
+        rule: &EnvExport,
+    ) -> &'a mut Command {
+        cmd.envs(rule.env_pairs(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueObj;
+
+    fn sample_dict() -> ValueDict {
+        let mut db = ValueObj::new();
+        db.insert("host".into(), ValueType::from("localhost"));
+        let mut dict = ValueDict::new();
+        dict.insert("app.port", ValueType::from(8080u64));
+        dict.insert("db", ValueType::Obj(db));
+        dict.insert("db_password", ValueType::from("p@ss"));
+        dict.insert("9lives", ValueType::from(true));
+        dict
+    }
+
+    #[test]
+    fn test_env_pairs() {
+        let rule = EnvExport::default();
+        let pairs = rule.env_pairs(&sample_dict());
+        assert_eq!(
+            pairs,
+            vec![
+                ("APP_PORT".to_string(), "8080".to_string()),
+                ("DB".to_string(), r#"{"host":"localhost"}"#.to_string()),
+                ("DB_PASSWORD".to_string(), "p@ss".to_string()),
+                ("_9LIVES".to_string(), "true".to_string()),
+            ]
+        );
+
+        let redacted = rule.redacted_pairs(&sample_dict());
+        assert_eq!(
+            redacted[2],
+            ("DB_PASSWORD".to_string(), "******".to_string())
+        );
+
+        let rule = EnvExport::default()
+            .with_prefix("MYAPP_".into())
+            .with_exclude_secrets(true);
+        let keys: Vec<_> = rule
+            .env_pairs(&sample_dict())
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["MYAPP_APP_PORT", "MYAPP_DB", "MYAPP_9LIVES"]);
+    }
+
+    #[test]
+    fn test_dotted_secret_keys() {
+        let mut dict = ValueDict::new();
+        dict.insert("api.key", ValueType::from("k"));
+        dict.insert("private.key", ValueType::from("pk"));
+        dict.insert("app.name", ValueType::from("demo"));
+
+        let rule = EnvExport::default();
+        assert_eq!(
+            rule.redacted_pairs(&dict),
+            vec![
+                ("API_KEY".to_string(), "******".to_string()),
+                ("PRIVATE_KEY".to_string(), "******".to_string()),
+                ("APP_NAME".to_string(), "demo".to_string()),
+            ]
+        );
+        let rule = rule.with_exclude_secrets(true);
+        assert_eq!(
+            rule.env_pairs(&dict),
+            vec![("APP_NAME".to_string(), "demo".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_env_name_collision() {
+        let mut dict = ValueDict::new();
+        dict.insert("app.port", ValueType::from(8080u64));
+        dict.insert("app_port", ValueType::from(9090u64));
+
+        let rule = EnvExport::default();
+        assert_eq!(
+            rule.env_pairs(&dict),
+            vec![("APP_PORT".to_string(), "8080".to_string())]
+        );
+        let err = rule.try_env_pairs(&dict).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`APP.PORT` and `APP_PORT` both export as `APP_PORT`")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_envs_for_command() {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("echo $APP_PORT");
+        let output = sample_dict().envs_for_command(&mut cmd).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "8080");
+    }
+}
@@ -0,0 +1,437 @@
+use std::{cmp::Ordering, env, fmt::Display};
+
+use orion_error::StructError;
+
+use super::{
+    EnvDict, ValueType,
+    error::{VarsReason, VarsResult},
+    global_vars::GlobalVars,
+};
+
+fn expr_err<T>(msg: String) -> VarsResult<T> {
+    Err(StructError::from(VarsReason::Expr(msg)))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Op(&'static str),
+}
+
+const OPS: [&str; 18] = [
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "+", "-", "*", "/", "%", "!", "?", ":", "(", ")",
+];
+
+fn tokenize(expr: &str) -> VarsResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = expr;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c == '\'' || c == '"' {
+            let Some(end) = rest[1..].find(c) else {
+                return expr_err(format!("unterminated string in `{expr}`"));
+            };
+            tokens.push(Token::Str(rest[1..end + 1].to_string()));
+            rest = &rest[end + 2..];
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|x: char| !(x.is_ascii_digit() || x == '.'))
+                .unwrap_or(rest.len());
+            let num = &rest[..len];
+            let token = if num.contains('.') {
+                num.parse().map(Token::Float).ok()
+            } else {
+                num.parse().map(Token::Int).ok()
+            };
+            match token {
+                Some(t) => tokens.push(t),
+                None => return expr_err(format!("invalid number `{num}` in `{expr}`")),
+            }
+            rest = &rest[len..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|x: char| !(x.is_ascii_alphanumeric() || matches!(x, '_' | '.')))
+                .unwrap_or(rest.len());
+            let word = &rest[..len];
+            tokens.push(match word {
+                "true" | "TRUE" => Token::Bool(true),
+                "false" | "FALSE" => Token::Bool(false),
+                _ => Token::Ident(word.to_string()),
+            });
+            rest = &rest[len..];
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            return expr_err(format!("unexpected `{c}` in `{expr}`"));
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug)]
+enum Expr {
+    Lit(Value),
+    Var(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+    Cond(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+struct ExprParser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn peek_op(&self) -> Option<&'static str> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        if self.peek_op() == Some(op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> VarsResult<()> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            expr_err(format!("expected `{op}` in `{}`", self.src))
+        }
+    }
+
+    fn cond(&mut self) -> VarsResult<Expr> {
+        let test = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(test);
+        }
+        let yes = self.cond()?;
+        self.expect(":")?;
+        let no = self.cond()?;
+        Ok(Expr::Cond(Box::new(test), Box::new(yes), Box::new(no)))
+    }
+
+    // 按优先级从低到高：|| && 比较 加减 乘除
+    fn binary(&mut self, level: usize) -> VarsResult<Expr> {
+        const LEVELS: [&[&str]; 5] = [
+            &["||"],
+            &["&&"],
+            &["==", "!=", "<", "<=", ">", ">="],
+            &["+", "-"],
+            &["*", "/", "%"],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        while let Some(op) = self.peek_op().filter(|op| LEVELS[level].contains(op)) {
+            self.pos += 1;
+            let right = self.binary(level + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> VarsResult<Expr> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let inner = self.cond()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        let expr = match self.tokens.get(self.pos) {
+            Some(Token::Ident(name)) => Expr::Var(name.clone()),
+            Some(Token::Str(s)) => Expr::Lit(Value::Str(s.clone())),
+            Some(Token::Int(i)) => Expr::Lit(Value::Int(*i)),
+            Some(Token::Float(f)) => Expr::Lit(Value::Float(*f)),
+            Some(Token::Bool(b)) => Expr::Lit(Value::Bool(*b)),
+            Some(Token::Op(op)) => return expr_err(format!("unexpected `{op}` in `{}`", self.src)),
+            None => return expr_err(format!("unexpected end of `{}`", self.src)),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Int(i) => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+impl From<&ValueType> for Value {
+    fn from(value: &ValueType) -> Self {
+        match value {
+            ValueType::String(s) => Value::Str(s.clone()),
+            ValueType::Bool(b) => Value::Bool(*b),
+            ValueType::Number(n) => i64::try_from(*n)
+                .map(Value::Int)
+                .unwrap_or(Value::Float(*n as f64)),
+            ValueType::Float(x) => Value::Float(*x),
+            other => Value::Str(other.to_string()),
+        }
+    }
+}
+
+impl Value {
+    // 变量值常以字符串形式出现（如来自环境变量），参与运算时按数字解释
+    fn as_number(&self) -> Option<Value> {
+        match self {
+            Value::Int(_) | Value::Float(_) => Some(self.clone()),
+            Value::Str(s) => {
+                let s = s.trim();
+                // 排除 `inf`、`NaN` 等 f64 可解析但不像数字的写法
+                if !s
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+'))
+                {
+                    return None;
+                }
+                s.parse()
+                    .map(Value::Int)
+                    .ok()
+                    .or_else(|| s.parse().map(Value::Float).ok())
+            }
+            Value::Bool(_) => None,
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            Value::Int(i) => *i as f64,
+            Value::Float(x) => *x,
+            _ => f64::NAN,
+        }
+    }
+
+    fn truthy(&self) -> VarsResult<bool> {
+        match self {
+            Value::Bool(b) => Ok(*b),
+            Value::Str(s) if s.eq_ignore_ascii_case("true") => Ok(true),
+            Value::Str(s) if s.eq_ignore_ascii_case("false") => Ok(false),
+            other => expr_err(format!("expected bool, found `{other}`")),
+        }
+    }
+}
+
+fn arith(op: &str, l: Value, r: Value) -> VarsResult<Value> {
+    let (Some(ln), Some(rn)) = (l.as_number(), r.as_number()) else {
+        if op == "+" && (matches!(l, Value::Str(_)) || matches!(r, Value::Str(_))) {
+            return Ok(Value::Str(format!("{l}{r}")));
+        }
+        return expr_err(format!("`{l} {op} {r}`: operands must be numbers"));
+    };
+    if let (Value::Int(a), Value::Int(b)) = (&ln, &rn) {
+        let out = match op {
+            "+" => a.checked_add(*b),
+            "-" => a.checked_sub(*b),
+            "*" => a.checked_mul(*b),
+            "/" => a.checked_div(*b),
+            _ => a.checked_rem(*b),
+        };
+        return match out {
+            Some(x) => Ok(Value::Int(x)),
+            None => expr_err(format!("`{a} {op} {b}`: overflow or division by zero")),
+        };
+    }
+    let (a, b) = (ln.as_f64(), rn.as_f64());
+    if matches!(op, "/" | "%") && b == 0.0 {
+        return expr_err(format!("`{a} {op} {b}`: division by zero"));
+    }
+    Ok(Value::Float(match op {
+        "+" => a + b,
+        "-" => a - b,
+        "*" => a * b,
+        "/" => a / b,
+        _ => a % b,
+    }))
+}
+
+fn compare(op: &str, l: Value, r: Value) -> VarsResult<Value> {
+    let ord = match (l.as_number(), r.as_number()) {
+        (Some(a), Some(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        _ if matches!(op, "==" | "!=") => Some(l.to_string().cmp(&r.to_string())),
+        _ => match (&l, &r) {
+            (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+            _ => return expr_err(format!("`{l} {op} {r}`: cannot compare")),
+        },
+    };
+    let Some(ord) = ord else {
+        return Ok(Value::Bool(op == "!="));
+    };
+    Ok(Value::Bool(match op {
+        "==" => ord == Ordering::Equal,
+        "!=" => ord != Ordering::Equal,
+        "<" => ord == Ordering::Less,
+        "<=" => ord != Ordering::Greater,
+        ">" => ord == Ordering::Greater,
+        _ => ord != Ordering::Less,
+    }))
+}
+
+fn eval(dict: &EnvDict, expr: &Expr) -> VarsResult<Value> {
+    match expr {
+        Expr::Lit(v) => Ok(v.clone()),
+        Expr::Var(name) => {
+            if let Some(found) = dict.get(name.as_str()) {
+                Ok(Value::from(found))
+            } else if let Some(found) = GlobalVars::get(name) {
+                Ok(Value::from(&found))
+            } else if let Ok(found) = env::var(name) {
+                Ok(Value::Str(found))
+            } else {
+                expr_err(format!("undefined variable `{name}`"))
+            }
+        }
+        Expr::Not(inner) => Ok(Value::Bool(!eval(dict, inner)?.truthy()?)),
+        Expr::Neg(inner) => arith("-", Value::Int(0), eval(dict, inner)?),
+        Expr::Cond(test, yes, no) => {
+            if eval(dict, test)?.truthy()? {
+                eval(dict, yes)
+            } else {
+                eval(dict, no)
+            }
+        }
+        Expr::Binary("&&", l, r) => Ok(Value::Bool(
+            eval(dict, l)?.truthy()? && eval(dict, r)?.truthy()?,
+        )),
+        Expr::Binary("||", l, r) => Ok(Value::Bool(
+            eval(dict, l)?.truthy()? || eval(dict, r)?.truthy()?,
+        )),
+        Expr::Binary(op, l, r) => {
+            let (l, r) = (eval(dict, l)?, eval(dict, r)?);
+            match *op {
+                "+" | "-" | "*" | "/" | "%" => arith(op, l, r),
+                _ => compare(op, l, r),
+            }
+        }
+    }
+}
+
+fn parse(expr: &str) -> VarsResult<Expr> {
+    let mut parser = ExprParser {
+        src: expr,
+        tokens: tokenize(expr)?,
+        pos: 0,
+    };
+    let out = parser.cond()?;
+    if parser.pos != parser.tokens.len() {
+        return expr_err(format!("unexpected trailing input in `{expr}`"));
+    }
+    Ok(out)
+}
+
+/// 计算 `${= ...}` 中的表达式，结果以字符串返回
+///
+/// 支持字符串/数字/布尔字面量、变量引用、`+ - * / %`、比较、`&& || !`、
+/// 括号与三元 `cond ? a : b`；`+` 在任一侧为非数字字符串时做拼接。
+/// 变量依次查 `dict`、`GlobalVars`、进程环境变量；不支持函数调用或任何其他代码执行。
+pub fn eval_expr(dict: &EnvDict, expr: &str) -> VarsResult<String> {
+    Ok(eval(dict, &parse(expr)?)?.to_string())
+}
+
+/// 表达式中引用的变量名
+pub(crate) fn expr_var_names(expr: &str) -> Vec<String> {
+    tokenize(expr)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|t| match t {
+            Token::Ident(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_dict() -> EnvDict {
+        let mut dict = EnvDict::new();
+        dict.insert("ENV", ValueType::from("prod"));
+        dict.insert("BASE", ValueType::from(3u64));
+        dict.insert("RATIO", ValueType::from("1.5"));
+        dict.insert("DEBUG", ValueType::from(false));
+        dict
+    }
+
+    #[test]
+    fn test_eval_expr() {
+        let dict = sample_dict();
+        assert_eq!(eval_expr(&dict, "ENV == 'prod' ? 5 : 1").unwrap(), "5");
+        assert_eq!(eval_expr(&dict, "ENV != \"prod\" ? 5 : 1").unwrap(), "1");
+        assert_eq!(eval_expr(&dict, "BASE * 2 + 1").unwrap(), "7");
+        assert_eq!(eval_expr(&dict, "(BASE + 1) * 2").unwrap(), "8");
+        assert_eq!(eval_expr(&dict, "BASE * RATIO").unwrap(), "4.5");
+        assert_eq!(eval_expr(&dict, "-BASE + 1").unwrap(), "-2");
+        assert_eq!(eval_expr(&dict, "ENV + '-' + BASE").unwrap(), "prod-3");
+        assert_eq!(eval_expr(&dict, "!DEBUG && BASE >= 3").unwrap(), "true");
+        assert_eq!(
+            eval_expr(&dict, "BASE > 1 ? (BASE > 2 ? 'big' : 'mid') : 'small'").unwrap(),
+            "big"
+        );
+        // 未选中的分支不求值
+        assert_eq!(eval_expr(&dict, "DEBUG ? MISSING : 0").unwrap(), "0");
+    }
+
+    #[test]
+    fn test_eval_expr_errors() {
+        let dict = sample_dict();
+        let cases = [
+            ("MISSING_VAR_X + 1", "undefined variable `MISSING_VAR_X`"),
+            ("BASE / 0", "division by zero"),
+            ("ENV * 2", "operands must be numbers"),
+            ("BASE ? 1 : 2", "expected bool"),
+            ("BASE +", "unexpected end"),
+            ("'abc", "unterminated string"),
+            ("BASE 1", "trailing input"),
+            ("BASE ? 1", "expected `:`"),
+            ("BASE; 1", "unexpected `;`"),
+        ];
+        for (expr, msg) in cases {
+            let err = eval_expr(&dict, expr).unwrap_err();
+            assert!(err.to_string().contains(msg), "{expr}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_expr_var_names() {
+        assert_eq!(
+            expr_var_names("ENV == 'prod' ? app.replicas : 1"),
+            vec!["ENV".to_string(), "app.replicas".to_string()]
+        );
+    }
+}
@@ -6,14 +6,25 @@ use std::{
 use log::info;
 use orion_error::{ErrorOwe, ErrorWith};
 
-use super::error::VarsResult;
+use super::{ValueDict, ValueType, error::VarsResult, global_vars::GlobalVars};
 
 pub fn setup_start_env_vars() -> VarsResult<()> {
-    unsafe { std::env::set_var("GXL_OS_SYS", format_os_sys().as_str()) };
+    let os_sys = format_os_sys();
+    unsafe { std::env::set_var("GXL_OS_SYS", os_sys.as_str()) };
     let start_root = current_dir().owe_sys().want("get current dir")?;
     unsafe { std::env::set_var("GXL_START_ROOT", start_root.display().to_string()) };
     let prj_root = find_project_define().unwrap_or(PathBuf::from("UNDEFIN"));
     unsafe { std::env::set_var("GXL_PRJ_ROOT", format!("{}", prj_root.display())) };
+    // 同步写入 GlobalVars，供不依赖进程环境的调用方使用
+    GlobalVars::set("GXL_OS_SYS", ValueType::from(os_sys));
+    GlobalVars::set(
+        "GXL_START_ROOT",
+        ValueType::from(start_root.display().to_string()),
+    );
+    GlobalVars::set(
+        "GXL_PRJ_ROOT",
+        ValueType::from(prj_root.display().to_string()),
+    );
     Ok(())
 }
 
@@ -33,6 +44,46 @@ fn get_os_info() -> (String, String, u64) {
     (arch, os_type, ver_major)
 }
 
+fn hostname() -> Option<String> {
+    env::var("HOSTNAME")
+        .or_else(|_| env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty())
+}
+
+/// 当前运行环境的标准化信息，键名见 `ValueDict::with_system_facts`
+pub(crate) fn system_facts() -> ValueDict {
+    let info = os_info::get();
+    let mut facts = ValueDict::new();
+    facts.insert("OS", ValueType::from(env::consts::OS));
+    facts.insert("ARCH", ValueType::from(env::consts::ARCH));
+    facts.insert("OS_FAMILY", ValueType::from(env::consts::FAMILY));
+    facts.insert(
+        "OS_DISTRO",
+        ValueType::from(info.os_type().to_string().to_lowercase()),
+    );
+    facts.insert("OS_VERSION", ValueType::from(info.version().to_string()));
+    facts.insert("OS_SYS", ValueType::from(format_os_sys()));
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    facts.insert("NUM_CPUS", ValueType::from(cpus as u64));
+    if let Some(host) = hostname() {
+        facts.insert("HOSTNAME", ValueType::from(host));
+    }
+    if let Ok(home) = env::var("HOME").or_else(|_| env::var("USERPROFILE")) {
+        facts.insert("HOME", ValueType::from(home));
+    }
+    if let Ok(user) = env::var("USER").or_else(|_| env::var("USERNAME")) {
+        facts.insert("USER", ValueType::from(user));
+    }
+    facts.insert(
+        "TMP_DIR",
+        ValueType::from(env::temp_dir().display().to_string()),
+    );
+    facts
+}
+
 fn format_os_sys() -> String {
     let (arch, os_type, ver_major) = get_os_info();
     format!("{arch}_{os_type}_{ver_major}",)
@@ -97,7 +148,9 @@ mod tests {
     use std::env;
     use tempfile::TempDir;
 
-    use crate::vars::global::{CwdGuard, find_project_define, get_os_info, setup_start_env_vars};
+    use crate::vars::global::{
+        CwdGuard, find_project_define, get_os_info, setup_start_env_vars, system_facts,
+    };
 
     #[test]
     fn test_get_os_info() {
@@ -112,6 +165,30 @@ mod tests {
         assert!(valid_archs.contains(&arch.as_str()));
     }
 
+    #[test]
+    fn test_system_facts() {
+        use crate::vars::{ValueDict, ValueType};
+
+        let facts = system_facts();
+        assert_eq!(
+            facts.get("OS"),
+            Some(&ValueType::from(std::env::consts::OS))
+        );
+        assert_eq!(
+            facts.get("ARCH"),
+            Some(&ValueType::from(std::env::consts::ARCH))
+        );
+        assert!(matches!(facts.get("NUM_CPUS"), Some(ValueType::Number(n)) if *n >= 1));
+        assert!(facts.contains_key("TMP_DIR"));
+
+        // 已有的键不会被覆盖
+        let mut dict = ValueDict::new();
+        dict.insert("OS", ValueType::from("darwin"));
+        let dict = dict.with_system_facts();
+        assert_eq!(dict.get("OS"), Some(&ValueType::from("darwin")));
+        assert!(dict.contains_key("ARCH"));
+    }
+
     #[test]
     fn test_setup_start_env_vars() {
         // 保存原始环境变量
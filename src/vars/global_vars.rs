@@ -0,0 +1,197 @@
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+    sync::{LazyLock, RwLock},
+};
+
+use super::{UpperKey, ValueDict, ValueType};
+
+static BASE: LazyLock<RwLock<ValueDict>> = LazyLock::new(|| RwLock::new(ValueDict::new()));
+
+thread_local! {
+    // 覆盖层按线程隔离，并行测试或多线程任务互不可见
+    static SCOPES: RefCell<Vec<(u64, ValueDict)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_SCOPE: Cell<u64> = const { Cell::new(0) };
+}
+
+fn read_base<T>(f: impl FnOnce(&ValueDict) -> T) -> T {
+    f(&BASE.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+fn write_base<T>(f: impl FnOnce(&mut ValueDict) -> T) -> T {
+    f(&mut BASE.write().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// 全局变量，由进程级基础层与当前线程的若干覆盖层组成
+///
+/// 查找时从当前线程最后压入的覆盖层向下到基础层；env_eval 在 `EnvDict` 未命中时
+/// 先查这里，再回退到进程环境变量。基础层初始为空，只在 [`GlobalVars::init`]
+/// 或 `setup_start_env_vars` 时写入，不读取进程环境。
+///
+/// 基础层为整个进程共享；[`GlobalVars::push_scope`] 压入的覆盖层只对当前线程可见，
+/// 不会传播到新线程或在其他线程上执行的异步任务。
+pub struct GlobalVars;
+
+impl GlobalVars {
+    /// 替换基础层
+    pub fn init(dict: ValueDict) {
+        write_base(|base| *base = dict);
+    }
+
+    /// 写入基础层
+    pub fn set<K: Into<UpperKey>>(key: K, value: ValueType) {
+        write_base(|base| {
+            base.insert(key, value);
+        });
+    }
+
+    pub fn get<S: AsRef<str>>(key: S) -> Option<ValueType> {
+        let key = UpperKey::fold(key.as_ref());
+        let key = key.as_ref();
+        SCOPES
+            .with_borrow(|scopes| {
+                scopes
+                    .iter()
+                    .rev()
+                    .find_map(|(_, dict)| dict.get(key))
+                    .cloned()
+            })
+            .or_else(|| read_base(|base| base.get(key).cloned()))
+    }
+
+    /// 合并后的当前视图，覆盖层优先
+    pub fn snapshot() -> ValueDict {
+        let mut out = ValueDict::new();
+        SCOPES.with_borrow(|scopes| {
+            for (_, dict) in scopes.iter().rev() {
+                out.merge(dict);
+            }
+        });
+        read_base(|base| out.merge(base));
+        out
+    }
+
+    /// 为当前线程压入覆盖层，返回的守卫在 drop 时移除该层
+    #[must_use = "the scope is removed when the guard is dropped"]
+    pub fn push_scope(dict: ValueDict) -> GlobalScope {
+        let id = NEXT_SCOPE.get() + 1;
+        NEXT_SCOPE.set(id);
+        SCOPES.with_borrow_mut(|scopes| scopes.push((id, dict)));
+        GlobalScope {
+            id,
+            _thread: PhantomData,
+        }
+    }
+}
+
+/// [`GlobalVars::push_scope`] 的守卫；按任意顺序 drop 都只移除自己的层
+///
+/// 覆盖层属于创建它的线程，守卫不能跨线程移动（`!Send`）。
+pub struct GlobalScope {
+    id: u64,
+    _thread: PhantomData<*const ()>,
+}
+
+impl GlobalScope {
+    /// 显式移除覆盖层，等同于 drop
+    pub fn pop(self) {}
+}
+
+impl Drop for GlobalScope {
+    fn drop(&mut self) {
+        // 线程退出时 thread_local 可能已销毁，此时覆盖层随之释放
+        let _ = SCOPES.try_with(|scopes| scopes.borrow_mut().retain(|(id, _)| *id != self.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::{EnvDict, EnvEvaluable};
+
+    #[test]
+    fn test_scopes() {
+        GlobalVars::set("GV_TEST_REGION", ValueType::from("base"));
+        assert_eq!(
+            GlobalVars::get("gv_test_region"),
+            Some(ValueType::from("base"))
+        );
+
+        let mut outer = ValueDict::new();
+        outer.insert("GV_TEST_REGION", ValueType::from("outer"));
+        outer.insert("GV_TEST_ONLY_OUTER", ValueType::from(true));
+        let outer = GlobalVars::push_scope(outer);
+
+        let mut inner = ValueDict::new();
+        inner.insert("GV_TEST_REGION", ValueType::from("inner"));
+        let inner = GlobalVars::push_scope(inner);
+        assert_eq!(
+            GlobalVars::get("GV_TEST_REGION"),
+            Some(ValueType::from("inner"))
+        );
+        assert_eq!(
+            GlobalVars::snapshot().get("GV_TEST_ONLY_OUTER"),
+            Some(&ValueType::from(true))
+        );
+
+        // 外层先释放，内层仍然生效
+        drop(outer);
+        assert_eq!(
+            GlobalVars::get("GV_TEST_REGION"),
+            Some(ValueType::from("inner"))
+        );
+        assert_eq!(GlobalVars::get("GV_TEST_ONLY_OUTER"), None);
+        inner.pop();
+        assert_eq!(
+            GlobalVars::get("GV_TEST_REGION"),
+            Some(ValueType::from("base"))
+        );
+    }
+
+    #[test]
+    fn test_scopes_are_thread_local() {
+        GlobalVars::set("GV_TEST_SHARED", ValueType::from("base"));
+        let mut scope = ValueDict::new();
+        scope.insert("GV_TEST_SHARED", ValueType::from("main"));
+        let _guard = GlobalVars::push_scope(scope);
+
+        let seen = std::thread::spawn(|| {
+            let mut scope = ValueDict::new();
+            scope.insert("GV_TEST_SHARED", ValueType::from("worker"));
+            let outer = GlobalVars::get("GV_TEST_SHARED");
+            let _guard = GlobalVars::push_scope(scope);
+            (outer, GlobalVars::get("GV_TEST_SHARED"))
+        })
+        .join()
+        .unwrap();
+        // 其他线程只能看到基础层
+        assert_eq!(
+            seen,
+            (
+                Some(ValueType::from("base")),
+                Some(ValueType::from("worker"))
+            )
+        );
+        assert_eq!(
+            GlobalVars::get("GV_TEST_SHARED"),
+            Some(ValueType::from("main"))
+        );
+    }
+
+    #[test]
+    fn test_env_eval_fallback() {
+        let mut scope = ValueDict::new();
+        scope.insert("GV_TEST_HOST", ValueType::from("global.example.com"));
+        let _guard = GlobalVars::push_scope(scope);
+
+        let empty = EnvDict::new();
+        assert_eq!(
+            "${GV_TEST_HOST}".to_string().env_eval(&empty),
+            "global.example.com"
+        );
+        // EnvDict 优先于全局变量
+        let mut dict = EnvDict::new();
+        dict.insert("GV_TEST_HOST", ValueType::from("local"));
+        assert_eq!("${GV_TEST_HOST}".to_string().env_eval(&dict), "local");
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use orion_error::{ErrorOwe, ErrorWith, StructError};
+
+use super::{
+    EnvDict, VarCollection,
+    error::{VarsReason, VarsResult},
+};
+
+/// 变量文件格式，按扩展名识别
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarsFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl VarsFormat {
+    pub fn from_path(path: &Path) -> VarsResult<Self> {
+        let ext = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase());
+        match ext.as_deref() {
+            Some("yml") | Some("yaml") => Ok(VarsFormat::Yaml),
+            Some("toml") => Ok(VarsFormat::Toml),
+            Some("json") => Ok(VarsFormat::Json),
+            _ => Err(StructError::from(VarsReason::Include(format!(
+                "unsupported vars file format: {}",
+                path.display()
+            )))),
+        }
+    }
+}
+
+impl VarCollection {
+    /// 按指定格式解析变量集合，不处理 `include`
+    pub fn parse(content: &str, format: VarsFormat) -> VarsResult<Self> {
+        let mut vars: VarCollection = match format {
+            VarsFormat::Yaml => serde_yaml::from_str(content).owe(VarsReason::Format)?,
+            VarsFormat::Toml => toml::from_str(content).owe(VarsReason::Format)?,
+            VarsFormat::Json => serde_json::from_str(content).owe(VarsReason::Format)?,
+        };
+        vars.mark_vars_scope();
+        Ok(vars)
+    }
+
+    /// 按指定格式序列化变量集合，各作用域内保持声明（合并）顺序
+    pub fn dump(&self, format: VarsFormat) -> VarsResult<String> {
+        match format {
+            VarsFormat::Yaml => serde_yaml::to_string(self).owe(VarsReason::Format),
+            VarsFormat::Toml => toml::to_string(self).owe(VarsReason::Format),
+            VarsFormat::Json => serde_json::to_string_pretty(self).owe(VarsReason::Format),
+        }
+    }
+
+    /// 写出带注释的示例变量文件，格式由扩展名决定（JSON 不含注释）
+    pub fn write_example<P: AsRef<Path>>(path: P) -> VarsResult<()> {
+        let path = path.as_ref();
+        let format = VarsFormat::from_path(path)?;
+        let mut content = String::new();
+        if format != VarsFormat::Json {
+            for line in EXAMPLE_HEADER.lines() {
+                content.push_str(format!("# {line}").trim_end());
+                content.push('\n');
+            }
+            content.push('\n');
+        }
+        content.push_str(&VarCollection::example().dump(format)?);
+        fs::write(path, content)
+            .owe_sys()
+            .want("write vars example")
+            .with(path.display().to_string())
+    }
+
+    /// 从文件加载变量集合，格式由扩展名决定（yml/yaml/toml/json）
+    ///
+    /// `include` 中的路径相对于当前文件所在目录解析，文件名部分可使用 `*`、`?` 通配；
+    /// 被包含文件按声明顺序先合并，当前文件的定义最后合并（后者覆盖前者）。
+    /// 循环包含会返回错误。
+    /// 定义上的 `when` 条件按当前平台解析，带 `flags` 的条件定义不会生效；
+    /// 需要自定义标志时使用 [`VarCollection::load_with`]。
+    pub fn load<P: AsRef<Path>>(path: P) -> VarsResult<Self> {
+        Self::load_with(path, &EnvDict::new())
+    }
+
+    /// 同 [`VarCollection::load`]，按 `dict` 解析 `when` 条件
+    ///
+    /// 每个文件先单独解析条件再参与合并，同一文件内同名的条件定义不会在合并时互相覆盖。
+    pub fn load_with<P: AsRef<Path>>(path: P, dict: &EnvDict) -> VarsResult<Self> {
+        Ok(load_tracked(path.as_ref(), dict)?.0)
+    }
+}
+
+const EXAMPLE_HEADER: &str = "\
+orion-variate 变量文件示例
+
+include   : 先加载的其他变量文件，相对本文件路径，文件名可用 * ? 通配
+immutable : 不可变变量，合并时不会被覆盖
+system    : 系统级变量，允许在任何上下文中修改
+module    : 模块级变量，只在同一模块内修改（别名 vars）
+
+每个变量字段：
+  name       变量名（查找时大小写不敏感）
+  value      默认值：字符串、布尔、整数、浮点、对象或列表
+  desc       说明（可选）
+  example    示例值（可选）
+  deprecated 废弃说明（可选）
+  schema     对象字段声明（可选）：name / type / required
+  items      列表元素声明（可选）：type / pattern
+  when       生效条件（可选）：os / arch / flags，不满足时忽略该定义
+  constraint 取值约束（可选）：!scope { beg, end } 限定数值范围
+  renamed_from 旧变量名列表（可选），用于迁移旧配置
+";
+
+/// 加载时涉及的文件与通配 `include`，供 watch 跟踪
+#[derive(Default)]
+pub(crate) struct Tracked {
+    /// 参与合并的全部文件（含被包含文件）
+    pub(crate) files: Vec<PathBuf>,
+    /// 通配 `include` 所在目录与文件名模式，匹配的文件增减时同样需要重新加载
+    pub(crate) globs: Vec<(PathBuf, String)>,
+}
+
+/// 加载并返回 [`Tracked`]，供 watch 跟踪
+pub(crate) fn load_tracked(path: &Path, dict: &EnvDict) -> VarsResult<(VarCollection, Tracked)> {
+    let mut stack = Vec::new();
+    let mut tracked = Tracked::default();
+    let vars = load_with_includes(path, dict, &mut stack, &mut tracked)?;
+    Ok((vars, tracked))
+}
+
+fn load_with_includes(
+    path: &Path,
+    dict: &EnvDict,
+    stack: &mut Vec<PathBuf>,
+    tracked: &mut Tracked,
+) -> VarsResult<VarCollection> {
+    let file = path
+        .canonicalize()
+        .owe_sys()
+        .want("load vars file")
+        .with(path.display().to_string())?;
+    if stack.contains(&file) {
+        let chain = stack
+            .iter()
+            .chain(std::iter::once(&file))
+            .map(|x| x.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(StructError::from(VarsReason::Include(format!(
+            "cycle detected: {chain}"
+        ))));
+    }
+    let format = VarsFormat::from_path(&file)?;
+    let content = fs::read_to_string(&file)
+        .owe_sys()
+        .want("read vars file")
+        .with(file.display().to_string())?;
+    let mut own = VarCollection::parse(&content, format).with(file.display().to_string())?;
+    own.locate_sources(&content, format, &file);
+
+    let base = file.parent().map(Path::to_path_buf).unwrap_or_default();
+    let includes = own.take_include();
+    // 合并按名称去重，须先解析条件，否则同名的条件定义只剩最后一个
+    let own = own.resolve(dict);
+    if !tracked.files.contains(&file) {
+        tracked.files.push(file.clone());
+    }
+    stack.push(file);
+    let mut merged = VarCollection::default();
+    for pattern in includes {
+        for inc in expand_include(&base, &pattern, tracked)? {
+            merged = merged.merge(load_with_includes(&inc, dict, stack, tracked)?);
+        }
+    }
+    stack.pop();
+    Ok(merged.merge(own))
+}
+
+fn expand_include(base: &Path, pattern: &str, tracked: &mut Tracked) -> VarsResult<Vec<PathBuf>> {
+    let target = base.join(pattern);
+    let name = target
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    if !name.contains(['*', '?']) {
+        return Ok(vec![target]);
+    }
+    let dir = target.parent().unwrap_or(base);
+    let found = glob_files(dir, name)
+        .owe_sys()
+        .want("expand include")
+        .with(pattern.to_string())?;
+    let glob = (dir.to_path_buf(), name.to_string());
+    if !tracked.globs.contains(&glob) {
+        tracked.globs.push(glob);
+    }
+    Ok(found)
+}
+
+/// `dir` 下文件名匹配通配模式 `name` 的文件，按路径排序
+pub(crate) fn glob_files(dir: &Path, name: &str) -> std::io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file()
+            && let Some(file_name) = path.file_name().and_then(|x| x.to_str())
+            && wildcard_match(name, file_name)
+        {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let n: Vec<char> = name.chars().collect();
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueType;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.yml", "base.yml"));
+        assert!(wildcard_match("vars-?.toml", "vars-1.toml"));
+        assert!(!wildcard_match("*.yml", "base.yaml"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn test_load_formats() {
+        let dir = TempDir::new().unwrap();
+        let toml_file = dir.path().join("vars.toml");
+        fs::write(
+            &toml_file,
+            r#"
+[[system]]
+name = "host"
+value = "example.com"
+
+[[module]]
+name = "port"
+value = 8080
+"#,
+        )
+        .unwrap();
+        let vars = VarCollection::load(&toml_file).unwrap();
+        let dict = vars.value_dict();
+        assert_eq!(dict.get("HOST"), Some(&ValueType::from("example.com")));
+        assert_eq!(dict.get("PORT"), Some(&ValueType::from(8080u64)));
+
+        let json_file = dir.path().join("vars.json");
+        fs::write(
+            &json_file,
+            r#"{"immutable": [{"name": "env", "value": "prod"}]}"#,
+        )
+        .unwrap();
+        let vars = VarCollection::load(&json_file).unwrap();
+        assert_eq!(vars.immutable_vars().len(), 1);
+        assert!(!vars.immutable_vars()[0].is_mutable());
+
+        assert!(VarCollection::load(dir.path().join("vars.ini")).is_err());
+    }
+
+    #[test]
+    fn test_load_with_includes() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("common")).unwrap();
+        fs::write(
+            dir.path().join("common/a.yml"),
+            "module:\n  - name: a\n    value: from_a\n  - name: shared\n    value: from_a\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("common/b.json"),
+            r#"{"module": [{"name": "b", "value": "from_b"}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("main.yml"),
+            "include:\n  - common/*.yml\n  - common/b.json\nmodule:\n  - name: shared\n    value: from_main\n",
+        )
+        .unwrap();
+
+        let vars = VarCollection::load(dir.path().join("main.yml")).unwrap();
+        assert!(vars.include().is_empty());
+        let dict = vars.value_dict();
+        assert_eq!(dict.get("A"), Some(&ValueType::from("from_a")));
+        assert_eq!(dict.get("B"), Some(&ValueType::from("from_b")));
+        assert_eq!(dict.get("SHARED"), Some(&ValueType::from("from_main")));
+    }
+
+    #[test]
+    fn test_write_example() {
+        let dir = TempDir::new().unwrap();
+        for name in ["vars.yml", "vars.toml", "vars.json"] {
+            let path = dir.path().join(name);
+            VarCollection::write_example(&path).unwrap();
+            let loaded = VarCollection::load(&path).unwrap();
+            assert_eq!(loaded, VarCollection::example());
+        }
+        let yaml = fs::read_to_string(dir.path().join("vars.yml")).unwrap();
+        assert!(yaml.starts_with("# orion-variate"));
+        assert!(yaml.contains("# immutable :"));
+        for field in ["items", "when", "renamed_from"] {
+            assert!(yaml.contains(&format!("#   {field} ")));
+        }
+    }
+
+    #[test]
+    fn test_load_with_conditions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("vars.yml");
+        fs::write(
+            &path,
+            r#"
+module:
+  - name: url
+    value: linux.tgz
+    when: { os: linux }
+  - name: url
+    value: macos.tgz
+    when: { os: macos }
+"#,
+        )
+        .unwrap();
+        for (os, expect) in [("linux", "linux.tgz"), ("darwin", "macos.tgz")] {
+            let mut dict = EnvDict::new();
+            dict.insert("OS", ValueType::from(os));
+            let vars = VarCollection::load_with(&path, &dict).unwrap();
+            assert_eq!(vars.module_vars().len(), 1);
+            assert_eq!(vars.value_dict().get("URL"), Some(&ValueType::from(expect)));
+        }
+        let mut dict = EnvDict::new();
+        dict.insert("OS", ValueType::from("windows"));
+        let vars = VarCollection::load_with(&path, &dict).unwrap();
+        assert!(vars.module_vars().is_empty());
+    }
+
+    #[test]
+    fn test_load_include_cycle() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.yml"), "include: [b.yml]\n").unwrap();
+        fs::write(dir.path().join("b.yml"), "include: [a.yml]\n").unwrap();
+        let err = VarCollection::load(dir.path().join("a.yml")).unwrap_err();
+        assert!(err.to_string().contains("cycle detected"));
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+use getset::Getters;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{VarCollection, VarsFormat, definition::Mutability};
+
+/// 变量定义在源文件中的位置，行列均从 1 开始
+#[derive(Getters, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct SourceLocation {
+    file: PathBuf,
+    line: usize,
+    column: usize,
+}
+
+impl SourceLocation {
+    pub fn new<P: Into<PathBuf>>(file: P, line: usize, column: usize) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+// 顶层段名对应的作用域，`vars` 为 `module` 的别名
+fn section_scope(key: &str) -> Option<Mutability> {
+    match key.trim().trim_matches(['"', '\'']) {
+        "immutable" => Some(Mutability::Immutable),
+        "system" => Some(Mutability::System),
+        "module" | "vars" => Some(Mutability::Module),
+        _ => None,
+    }
+}
+
+// 引号字符串（含两端引号）的字节长度：`"` 内 `\` 转义下一个字符，`'` 内 `''` 表示一个引号
+fn quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((pos, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if quote == '\'' && chars.next_if(|(_, c)| *c == '\'').is_some() {
+                continue;
+            }
+            return Some(pos + 1);
+        }
+    }
+    None
+}
+
+// 解析 `name` 键之后的 `: value` / `= value`，返回值的原始文本，引号字符串保留引号
+fn name_value(rest: &str) -> Option<&str> {
+    let rest = rest.strip_prefix(['"', '\'']).unwrap_or(rest).trim_start();
+    let rest = rest.strip_prefix([':', '='])?.trim_start();
+    if let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        return quoted_len(rest, quote).map(|end| &rest[..end]);
+    }
+    let end = rest.find([',', '}', '#']).unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
+
+// 按格式还原引号字符串（如 `"caf\u00e9"`、`'it''s'`），无法解析时去掉引号保留原文
+fn unquote(raw: &str, format: VarsFormat) -> Cow<'_, str> {
+    let Some(body) = raw
+        .strip_prefix(['"', '\''])
+        .and_then(|x| x.strip_suffix(['"', '\'']))
+    else {
+        return Cow::Borrowed(raw);
+    };
+    if !body.contains('\\') && !body.contains("''") {
+        return Cow::Borrowed(body);
+    }
+    let parsed = match format {
+        VarsFormat::Yaml => serde_yaml::from_str::<String>(raw).ok(),
+        VarsFormat::Json => serde_json::from_str::<String>(raw).ok(),
+        VarsFormat::Toml => toml::from_str::<toml::Table>(&format!("v = {raw}"))
+            .ok()
+            .and_then(|t| t.get("v")?.as_str().map(str::to_string)),
+    };
+    parsed.map_or(Cow::Borrowed(body), Cow::Owned)
+}
+
+// 字节偏移转为从 1 开始的字符列号
+fn char_column(line: &str, byte: usize) -> usize {
+    line[..byte].chars().count() + 1
+}
+
+// `text` 以（可带引号的）`name` 键开头时返回其值
+fn leading_name(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix(['"', '\'']).unwrap_or(text);
+    let rest = rest.strip_prefix("name")?;
+    if rest.starts_with(is_ident_char) {
+        return None;
+    }
+    name_value(rest)
+}
+
+// 单行流式映射 `{ a: 1, name: x }` 中第一层的 `name` 键，返回（键偏移，值）
+fn flow_name(body: &str) -> Option<(usize, &str)> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut key_start = true;
+    for (pos, c) in body.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if q == '"' && c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '{' | '[' => {
+                depth += 1;
+                key_start = depth == 1;
+                continue;
+            }
+            '}' | ']' => depth -= 1,
+            ',' => {
+                key_start = depth == 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            _ => {}
+        }
+        if key_start
+            && depth == 1
+            && let Some(value) = leading_name(&body[pos..])
+        {
+            return Some((pos, value));
+        }
+        if c == '"' || c == '\'' {
+            quote = Some(c);
+        }
+        key_start = false;
+    }
+    None
+}
+
+type Entry<'a> = (Mutability, Cow<'a, str>, usize, usize);
+
+// YAML：只取各作用域列表项这一层的 `name`，跳过注释与 schema 等嵌套列表
+fn yaml_entries(content: &str) -> Vec<Entry<'_>> {
+    let mut found = Vec::new();
+    let mut scope = None;
+    // （列表项 `-` 的缩进，列表项内键的缩进）
+    let mut item: Option<(usize, usize)> = None;
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        if indent == 0 && !trimmed.starts_with('-') {
+            scope = trimmed
+                .split_once(':')
+                .and_then(|(key, _)| section_scope(key));
+            item = None;
+            continue;
+        }
+        let Some(scope) = scope.clone() else {
+            continue;
+        };
+        let (body, col) = if let Some(rest) = trimmed.strip_prefix('-') {
+            let body = rest.trim_start();
+            let col = indent + (trimmed.len() - body.len());
+            let (dash, key) = *item.get_or_insert((indent, col));
+            if dash != indent {
+                continue;
+            }
+            item = Some((dash, key.min(col)));
+            (body, col)
+        } else if item.is_some_and(|(_, key)| key == indent) {
+            (trimmed, indent)
+        } else {
+            continue;
+        };
+        let located = if body.starts_with('{') {
+            flow_name(body).map(|(pos, value)| (value, col + pos))
+        } else {
+            leading_name(body).map(|value| (value, col))
+        };
+        if let Some((value, col)) = located {
+            let value = unquote(value, VarsFormat::Yaml);
+            found.push((scope, value, idx + 1, char_column(line, col)));
+        }
+    }
+    found
+}
+
+// TOML：只取 `[[immutable]]`/`[[system]]`/`[[module]]` 表头下的 `name` 键
+fn toml_entries(content: &str) -> Vec<Entry<'_>> {
+    let mut found = Vec::new();
+    let mut scope = None;
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            scope = trimmed
+                .strip_prefix("[[")
+                .and_then(|rest| rest.split_once("]]"))
+                .and_then(|(table, _)| section_scope(table));
+            continue;
+        }
+        if let Some(scope) = scope.clone()
+            && let Some(value) = leading_name(trimmed)
+        {
+            let value = unquote(value, VarsFormat::Toml);
+            found.push((
+                scope,
+                value,
+                idx + 1,
+                char_column(line, line.len() - trimmed.len()),
+            ));
+        }
+    }
+    found
+}
+
+// JSON：只取 `{ "<scope>": [ { "name": .. } ] }` 第三层对象的 `name` 键
+fn json_entries(content: &str) -> Vec<Entry<'_>> {
+    let mut found = Vec::new();
+    let mut stack = Vec::new();
+    let mut scope = None;
+    let (mut line, mut line_start) = (1, 0);
+    let mut chars = content.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            '\n' => {
+                line += 1;
+                line_start = pos + 1;
+            }
+            '{' | '[' => stack.push(c),
+            '}' | ']' => {
+                stack.pop();
+            }
+            '"' => {
+                let start = pos + 1;
+                let mut end = content.len();
+                while let Some((p, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            end = p;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let key = &content[start..end];
+                let rest = content[end..].get(1..).unwrap_or_default();
+                if !rest.trim_start().starts_with(':') {
+                    continue;
+                }
+                if stack.len() == 1 {
+                    scope = section_scope(key);
+                } else if stack == ['{', '[', '{']
+                    && key == "name"
+                    && let Some(scope) = scope.clone()
+                    && let Some(value) = name_value(rest)
+                {
+                    let value = unquote(value, VarsFormat::Json);
+                    found.push((
+                        scope,
+                        value,
+                        line,
+                        char_column(&content[line_start..], pos - line_start),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+impl VarCollection {
+    /// 按 `name` 键出现的位置为各定义记录来源
+    ///
+    /// 只识别各作用域定义列表这一层的 `name`，同一作用域内的同名定义按文件中的顺序依次对应。
+    /// 列号按字符计；引号中的转义（如 `"caf\u00e9"`）按各格式的规则还原后再与名称比较。
+    ///
+    /// 按行扫描，不是完整的解析器。以下写法中的定义不记录位置：
+    /// - YAML 块标量（`name: |`）、跨行的流式映射，以及锚点、别名与合并键
+    /// - TOML 内联表数组（`module = [{ name = "a" }]`）与点分键（`module.name = ..`）
+    /// - JSON 中键名含转义的 `"name"`
+    pub(crate) fn locate_sources(&mut self, content: &str, format: VarsFormat, file: &Path) {
+        let entries = match format {
+            VarsFormat::Yaml => yaml_entries(content),
+            VarsFormat::Toml => toml_entries(content),
+            VarsFormat::Json => json_entries(content),
+        };
+        let mut entries: Vec<_> = entries.into_iter().map(Some).collect();
+        for (scope, var) in self.iter_all_mut() {
+            let slot = entries.iter_mut().find(|e| {
+                e.as_ref()
+                    .is_some_and(|(s, value, _, _)| *s == scope && value == var.name())
+            });
+            if let Some((_, _, line, column)) = slot.and_then(Option::take) {
+                var.set_source(Some(SourceLocation::new(file, line, column)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::VarsFormat;
+    use std::path::Path;
+
+    fn owned(entries: Vec<Entry<'_>>) -> Vec<(Mutability, String, usize, usize)> {
+        entries
+            .into_iter()
+            .map(|(scope, name, line, col)| (scope, name.into_owned(), line, col))
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_by_format() {
+        use Mutability::*;
+        let yaml = "module:\n  - name: port\n    value: 80\n  - { value: x, \"name\": \"host\" }\n  - value: 1\n    name: filename\n";
+        assert_eq!(
+            owned(yaml_entries(yaml)),
+            vec![
+                (Module, "port".into(), 2, 5),
+                (Module, "host".into(), 4, 17),
+                (Module, "filename".into(), 6, 5)
+            ]
+        );
+        let toml = "[[system]]\nname = \"port\"\nschema = [{ name = \"x\", type = \"string\" }]\n[[system.schema]]\nname = \"y\"\n";
+        assert_eq!(
+            owned(toml_entries(toml)),
+            vec![(System, "port".into(), 2, 1)]
+        );
+        let json = "{\"immutable\": [\n  {\"name\": \"port\", \"value\": {\"name\": \"x\"}}\n]}";
+        assert_eq!(
+            owned(json_entries(json)),
+            vec![(Immutable, "port".into(), 2, 4)]
+        );
+    }
+
+    #[test]
+    fn test_entries_unicode_and_escapes() {
+        use Mutability::*;
+        // 列号按字符计，非 ASCII 字符不按字节展开
+        let yaml = "module:\n  - { désc: \"é\", name: \"caf\\u00e9\" }\n  - name: 'it''s'\n  - name: \"a\\\"b\"\n";
+        assert_eq!(
+            owned(yaml_entries(yaml)),
+            vec![
+                (Module, "café".into(), 2, 18),
+                (Module, "it's".into(), 3, 5),
+                (Module, "a\"b".into(), 4, 5),
+            ]
+        );
+        let toml = "[[module]]\nname = \"caf\\u00e9\"\n";
+        assert_eq!(
+            owned(toml_entries(toml)),
+            vec![(Module, "café".into(), 2, 1)]
+        );
+        let json = "{\"system\": [{\"value\": \"é\", \"name\": \"caf\\u00e9\"}]}";
+        assert_eq!(
+            owned(json_entries(json)),
+            vec![(System, "café".into(), 1, 28)]
+        );
+
+        let mut vars = VarCollection::parse(json, VarsFormat::Json).unwrap();
+        vars.locate_sources(json, VarsFormat::Json, Path::new("vars.json"));
+        let (_, var) = vars.iter_all().next().unwrap();
+        assert_eq!(var.name(), "café");
+        assert_eq!(var.source().as_ref().unwrap().to_string(), "vars.json:1:28");
+    }
+
+    #[test]
+    fn test_locate_sources() {
+        let yaml = r#"
+module:
+  - name: db
+    value: { host: a }
+    schema:
+      - name: host
+        type: string
+  # - name: host
+  - name: host
+    value: a
+  - name: url
+    value: b
+    when: { os: macos }
+immutable:
+  - name: url
+    value: c
+"#;
+        let mut vars = VarCollection::parse(yaml, VarsFormat::Yaml).unwrap();
+        vars.locate_sources(yaml, VarsFormat::Yaml, Path::new("vars.yml"));
+        let located: Vec<_> = vars
+            .iter_all()
+            .map(|(_, v)| (v.name().as_str(), v.source().as_ref().unwrap().to_string()))
+            .collect();
+        assert_eq!(
+            located,
+            vec![
+                ("url", "vars.yml:15:5".to_string()),
+                ("db", "vars.yml:3:5".to_string()),
+                ("host", "vars.yml:9:5".to_string()),
+                ("url", "vars.yml:11:5".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_records_location() {
+        use crate::vars::OriginDict;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("vars.toml");
+        std::fs::write(&path, "[[system]]\nname = \"port\"\nvalue = 80\n").unwrap();
+        let vars = VarCollection::load(&path).unwrap();
+        let origin = OriginDict::from(vars);
+        let location = origin.get("PORT").unwrap().location().clone().unwrap();
+        assert_eq!(location.file(), &path.canonicalize().unwrap());
+        assert_eq!((*location.line(), *location.column()), (2, 1));
+    }
+}
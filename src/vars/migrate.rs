@@ -0,0 +1,164 @@
+use getset::Getters;
+
+use super::{UpperKey, ValueDict, VarCollection, VarDefinition};
+
+/// 字典中出现的旧变量名
+#[derive(Getters, Clone, Debug, PartialEq, Eq)]
+#[getset(get = "pub")]
+pub struct LegacyName {
+    /// 字典中的旧键（大写）
+    legacy: String,
+    /// 对应的当前变量名
+    current: String,
+    /// 字典同时包含当前名称，旧键的值将被忽略
+    shadowed: bool,
+}
+
+impl VarCollection {
+    /// 按名称查找定义，当前名称优先，其次匹配 `renamed_from` 中的旧名称
+    ///
+    /// 名称按 `UpperKey` 折叠比较，与 `ValueDict` 的键一致。
+    pub fn find(&self, name: &str) -> Option<&VarDefinition> {
+        let key = UpperKey::fold(name);
+        let all = || self.iter_all().map(|(_, v)| v);
+        all()
+            .find(|v| UpperKey::fold(v.name()) == key)
+            .or_else(|| all().find(|v| v.is_known_as(name)))
+    }
+
+    fn rename_target(&self, key: &str) -> Option<&VarDefinition> {
+        self.find(key)
+            .filter(|v| UpperKey::fold(v.name()) != UpperKey::fold(key))
+    }
+
+    /// 列出 `dict` 中使用旧名称的键，保持字典顺序
+    pub fn migration_report(&self, dict: &ValueDict) -> Vec<LegacyName> {
+        dict.keys()
+            .filter_map(|key| {
+                let var = self.rename_target(key.as_str())?;
+                Some(LegacyName {
+                    legacy: key.as_str().to_string(),
+                    current: var.name().clone(),
+                    shadowed: dict.get_case_insensitive(var.name()).is_some(),
+                })
+            })
+            .collect()
+    }
+
+    /// 将 `dict` 中的旧键改为当前名称，每个旧键调用一次 `on_legacy`
+    ///
+    /// 旧键保持原有位置；字典已包含当前名称时以当前名称的值为准，旧键被丢弃。
+    pub fn migrate<F: FnMut(&LegacyName)>(&self, dict: &ValueDict, mut on_legacy: F) -> ValueDict {
+        let report = self.migration_report(dict);
+        let mut out = ValueDict::new();
+        for (key, value) in dict.iter() {
+            match report.iter().find(|r| r.legacy == key.as_str()) {
+                Some(legacy) => {
+                    on_legacy(legacy);
+                    if !legacy.shadowed {
+                        out.insert(legacy.current.as_str(), value.clone());
+                    }
+                }
+                None => {
+                    out.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueType;
+
+    fn sample() -> VarCollection {
+        VarCollection::define(vec![
+            VarDefinition::from(("http_port", 8080u64))
+                .with_renamed_from(vec!["port".into(), "listen_port".into()]),
+            VarDefinition::from(("log_level", "info")).with_renamed_from(vec!["verbosity".into()]),
+        ])
+    }
+
+    #[test]
+    fn test_find_by_legacy_name() {
+        let vars = sample();
+        assert_eq!(vars.find("PORT").unwrap().name(), "http_port");
+        assert_eq!(vars.find("http_port").unwrap().name(), "http_port");
+        assert!(vars.find("missing").is_none());
+    }
+
+    #[test]
+    fn test_find_non_ascii() {
+        let vars = VarCollection::define(vec![
+            VarDefinition::from(("café", "open")).with_renamed_from(vec!["straße".into()]),
+        ]);
+        assert_eq!(vars.find("CAFÉ").unwrap().name(), "café");
+        assert_eq!(vars.find("STRASSE").unwrap().name(), "café");
+        assert!(vars.find("CAFÉ").unwrap().is_known_as("Straße"));
+
+        let mut dict = ValueDict::new();
+        dict.insert("straße", ValueType::from("closed"));
+        let report = vars.migration_report(&dict);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].current(), "café");
+        let migrated = vars.migrate(&dict, |_| {});
+        assert_eq!(migrated.get("CAFÉ"), Some(&ValueType::from("closed")));
+        // 当前名称本身不算旧名称
+        let mut dict = ValueDict::new();
+        dict.insert("Café", ValueType::from("open"));
+        assert!(vars.migration_report(&dict).is_empty());
+    }
+
+    #[test]
+    fn test_migrate() {
+        let mut dict = ValueDict::new();
+        dict.insert("port", ValueType::from(9090u64));
+        dict.insert("name", ValueType::from("demo"));
+        dict.insert("log_level", ValueType::from("debug"));
+        dict.insert("verbosity", ValueType::from("trace"));
+
+        let vars = sample();
+        assert_eq!(
+            vars.migration_report(&dict),
+            vec![
+                LegacyName {
+                    legacy: "PORT".into(),
+                    current: "http_port".into(),
+                    shadowed: false,
+                },
+                LegacyName {
+                    legacy: "VERBOSITY".into(),
+                    current: "log_level".into(),
+                    shadowed: true,
+                },
+            ]
+        );
+
+        let mut warned = Vec::new();
+        let migrated = vars.migrate(&dict, |l| warned.push(l.legacy().clone()));
+        assert_eq!(warned, vec!["PORT", "VERBOSITY"]);
+        let keys: Vec<_> = migrated.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["HTTP_PORT", "NAME", "LOG_LEVEL"]);
+        assert_eq!(migrated.get("HTTP_PORT"), Some(&ValueType::from(9090u64)));
+        assert_eq!(migrated.get("LOG_LEVEL"), Some(&ValueType::from("debug")));
+    }
+
+    #[test]
+    fn test_renamed_from_yaml() {
+        let yaml = r#"
+module:
+  - name: http_port
+    value: 8080
+    renamed_from: [port]
+"#;
+        let vars: VarCollection = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            vars.module_vars()[0].renamed_from(),
+            &vec!["port".to_string()]
+        );
+        let out = serde_yaml::to_string(&vars).unwrap();
+        assert!(out.contains("renamed_from"));
+    }
+}
@@ -1,30 +1,112 @@
+// `types` 与 `dict` 只依赖 `alloc`，其余模块需要 `std`
+mod dict;
+mod types;
+
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
 mod collection;
+#[cfg(feature = "std")]
+mod condition;
+#[cfg(feature = "std")]
 mod constraint;
+#[cfg(feature = "std")]
+mod convert;
+#[cfg(feature = "std")]
 mod definition;
-mod dict;
+#[cfg(feature = "std")]
 mod env_eval;
+#[cfg(feature = "std")]
 mod error;
+#[cfg(feature = "std")]
+mod export;
+#[cfg(feature = "std")]
+mod expr;
+#[cfg(feature = "std")]
 mod global;
+#[cfg(feature = "std")]
+mod global_vars;
+#[cfg(feature = "std")]
+mod loader;
+#[cfg(feature = "std")]
+mod location;
+#[cfg(feature = "std")]
+mod migrate;
+#[cfg(feature = "std")]
 mod origin;
+#[cfg(feature = "std")]
+mod overrides;
+#[cfg(feature = "std")]
 mod parse;
-mod types;
-pub use collection::VarCollection;
+#[cfg(feature = "std")]
+mod schema;
+#[cfg(feature = "std")]
+mod secret;
+#[cfg(feature = "std")]
+mod watch;
+
+pub use dict::{FlattenKeys, NAMESPACE_SEP, ValueDict, namespaced_key};
+pub use types::EnvDict;
+#[cfg(not(feature = "std"))]
+pub use types::{FnvHasher, ValueHasher};
+pub use types::{UpperKey, ValueObj, ValueType, ValueVec};
+
+#[cfg(feature = "std")]
+pub use collection::{CaseCollision, VarCollection};
+#[cfg(feature = "std")]
+pub use condition::VarCondition;
+#[cfg(feature = "std")]
 pub use constraint::{ValueConstraint, ValueScope};
+#[cfg(feature = "std")]
+pub use convert::FromValue;
+#[cfg(feature = "std")]
 pub use definition::{Mutability, VarDefinition, VarToValue};
-pub use dict::ValueDict;
-pub use env_eval::extract_env_var_names;
+#[cfg(feature = "std")]
+pub use env_eval::{extract_env_var_names, try_expand_env_vars};
+#[cfg(feature = "std")]
+pub use error::{VarsReason, VarsResult};
+#[cfg(feature = "std")]
+pub use export::EnvExport;
+#[cfg(feature = "std")]
+pub use expr::eval_expr;
+#[cfg(feature = "std")]
 pub use global::{
     CwdGuard, find_project_define as find_project_root,
     find_project_define_base as find_project_root_from, setup_start_env_vars,
 };
+#[cfg(feature = "std")]
+pub use global_vars::{GlobalScope, GlobalVars};
+#[cfg(feature = "std")]
+pub use loader::VarsFormat;
+#[cfg(feature = "std")]
+pub use location::SourceLocation;
+#[cfg(feature = "std")]
+pub use migrate::LegacyName;
+#[cfg(feature = "std")]
+pub use origin::MutabilityViolation;
+#[cfg(feature = "std")]
 pub use origin::OriginDict;
+#[cfg(feature = "std")]
 pub use origin::OriginValue;
+#[cfg(feature = "std")]
+pub use overrides::{infer_value, parse_set, parse_set_file};
+#[cfg(feature = "std")]
+pub use schema::{FieldSchema, ListPattern, ListSchema, ObjSchema, ValueKind};
+#[cfg(feature = "std")]
+pub use secret::{
+    FileSecretResolver, SecretResolver, register_secret_resolver, unregister_secret_resolver,
+};
+#[cfg(feature = "std")]
 pub use types::EnvChecker;
-pub use types::EnvDict;
+#[cfg(feature = "std")]
 pub use types::EnvEvaluable;
 // 向后兼容别名
+#[cfg(feature = "std")]
 pub use global::find_project_define;
+#[cfg(feature = "std")]
 pub use global::find_project_define_base;
+#[cfg(feature = "std")]
 #[deprecated]
 pub use types::EnvEvaluable as EnvEvalable;
-pub use types::{UpperKey, ValueObj, ValueType, ValueVec};
+#[cfg(feature = "std")]
+pub use watch::{VarsWatcher, WatchOptions};
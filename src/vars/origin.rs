@@ -1,12 +1,19 @@
+use std::fmt::{Display, Formatter};
+
 use derive_more::Deref;
 use getset::{Getters, WithSetters};
 use indexmap::IndexMap;
+use orion_error::StructError;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::vars::types::UpperKey;
 
 use super::{
-    EnvDict, EnvEvaluable, ValueDict, VarCollection, definition::Mutability, dict::ValueMap,
+    EnvDict, EnvEvaluable, ValueDict, VarCollection,
+    definition::Mutability,
+    dict::{FlattenKeys, ValueMap, namespaced_key},
+    error::{VarsReason, VarsResult},
+    location::SourceLocation,
     types::ValueType,
 };
 
@@ -36,6 +43,10 @@ pub struct OriginValue {
     #[getset(get = "pub", set_with = "pub")]
     #[serde(default, skip_serializing_if = "Mutability::is_default")]
     mutability: Mutability,
+    /// 来自变量文件时定义所在位置
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<SourceLocation>,
 }
 
 impl EnvEvaluable<OriginValue> for OriginValue {
@@ -44,10 +55,34 @@ impl EnvEvaluable<OriginValue> for OriginValue {
             origin: self.origin,
             value: self.value.env_eval(dict),
             mutability: self.mutability,
+            location: self.location,
         }
     }
 }
 
+/// 合并时对不可变变量的覆盖尝试
+#[derive(Getters, Clone, Debug, Serialize, PartialEq)]
+#[getset(get = "pub")]
+pub struct MutabilityViolation {
+    key: UpperKey,
+    existing_origin: Option<String>,
+    attempted_origin: Option<String>,
+    attempted_value: ValueType,
+}
+
+impl Display for MutabilityViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is immutable (defined by {}), override from {} with {} rejected",
+            self.key.as_str(),
+            self.existing_origin.as_deref().unwrap_or("<unknown>"),
+            self.attempted_origin.as_deref().unwrap_or("<unknown>"),
+            self.attempted_value
+        )
+    }
+}
+
 #[derive(Getters, Clone, Debug, Serialize, Deserialize, PartialEq, Deref, Default)]
 pub struct OriginDict {
     dict: OriginMap,
@@ -66,6 +101,7 @@ impl From<ValueType> for OriginValue {
             value,
             origin: None,
             mutability: Mutability::default(),
+            location: None,
         }
     }
 }
@@ -75,6 +111,7 @@ impl From<&str> for OriginValue {
             origin: None,
             value: ValueType::from(value),
             mutability: Mutability::default(),
+            location: None,
         }
     }
 }
@@ -107,19 +144,25 @@ impl From<VarCollection> for OriginDict {
         for item in value.immutable_vars() {
             dict.insert(
                 item.name().to_string().into(),
-                OriginValue::from(item.value().clone()).with_mutability(item.mutability().clone()),
+                OriginValue::from(item.value().clone())
+                    .with_mutability(item.mutability().clone())
+                    .with_location(item.source().clone()),
             );
         }
         for item in value.system_vars() {
             dict.insert(
                 item.name().to_string().into(),
-                OriginValue::from(item.value().clone()).with_mutability(item.mutability().clone()),
+                OriginValue::from(item.value().clone())
+                    .with_mutability(item.mutability().clone())
+                    .with_location(item.source().clone()),
             );
         }
         for item in value.module_vars() {
             dict.insert(
                 item.name().to_string().into(),
-                OriginValue::from(item.value().clone()).with_mutability(item.mutability().clone()),
+                OriginValue::from(item.value().clone())
+                    .with_mutability(item.mutability().clone())
+                    .with_location(item.source().clone()),
             );
         }
 
@@ -152,6 +195,46 @@ impl OriginDict {
         }
         self
     }
+    /// 与 `merge` 相同的合并规则，但返回被拒绝的不可变覆盖记录
+    pub fn merge_checked(&mut self, other: &Self) -> Vec<MutabilityViolation> {
+        let violations = self.find_violations(other);
+        self.merge(other);
+        violations
+    }
+
+    /// 存在不可变覆盖时整体失败且不修改 `self`
+    pub fn merge_strict(&mut self, other: &Self) -> VarsResult<()> {
+        let violations = self.find_violations(other);
+        if !violations.is_empty() {
+            let detail = violations
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(StructError::from(VarsReason::Immutable(detail)));
+        }
+        self.merge(other);
+        Ok(())
+    }
+
+    fn find_violations(&self, other: &Self) -> Vec<MutabilityViolation> {
+        let mut violations = Vec::new();
+        for (k, v) in other.iter() {
+            if let Some(x) = self.get(k)
+                && !x.is_mutable()
+                && x.value() != v.value()
+            {
+                violations.push(MutabilityViolation {
+                    key: k.clone(),
+                    existing_origin: x.origin().clone(),
+                    attempted_origin: v.origin().clone(),
+                    attempted_value: v.value().clone(),
+                });
+            }
+        }
+        violations
+    }
+
     pub fn merge(&mut self, other: &Self) {
         for (k, v) in other.iter() {
             if let Some(x) = self.get(k) {
@@ -164,6 +247,15 @@ impl OriginDict {
             }
         }
     }
+    /// 返回键按字典序排列的副本，值中的嵌套对象同样排序；默认保持插入顺序
+    pub fn sorted(&self) -> Self {
+        let mut dict = self.dict.clone();
+        for v in dict.values_mut() {
+            v.value = v.value.sorted();
+        }
+        dict.sort_unstable_keys();
+        Self { dict }
+    }
     pub fn export_value(&self) -> ValueMap {
         let mut map = ValueMap::new();
         for (k, v) in &self.dict {
@@ -182,13 +274,33 @@ impl OriginDict {
         map
     }
     pub fn get_case_insensitive<S: AsRef<str>>(&self, key: S) -> Option<&OriginValue> {
-        let upper_key = UpperKey::from(key.as_ref());
-        self.dict.get(&upper_key)
+        self.dict.get(UpperKey::fold(key.as_ref()).as_ref())
     }
     #[deprecated(note = "renamed to get_case_insensitive()")]
     pub fn ucase_get<S: AsRef<str>>(&self, key: S) -> Option<&OriginValue> {
         self.get_case_insensitive(key)
     }
+
+    /// 先查找 `namespace.key`，不存在时回退到 `key`
+    pub fn get_scoped<S: AsRef<str>>(&self, namespace: &str, key: S) -> Option<&OriginValue> {
+        self.get_case_insensitive(namespaced_key(namespace, key.as_ref()))
+            .or_else(|| self.get_case_insensitive(key))
+    }
+
+    /// 将 `other` 的键置于 `namespace` 下合并，合并规则同 `merge`
+    pub fn merge_namespaced(&mut self, namespace: &str, other: &Self) {
+        let mut scoped = OriginDict::new();
+        for (k, v) in other.iter() {
+            scoped
+                .dict
+                .insert(namespaced_key(namespace, k.as_str()).into(), v.clone());
+        }
+        self.merge(&scoped);
+    }
+
+    pub fn export_flat(&self, style: FlattenKeys) -> ValueDict {
+        self.export_dict().flatten(style)
+    }
 }
 
 #[cfg(test)]
@@ -531,6 +643,92 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod violation_tests {
+    use super::*;
+
+    fn base_dict() -> OriginDict {
+        let mut dict = OriginDict::new();
+        dict.insert("locked", ValueType::from("v1"));
+        dict.insert("open", ValueType::from("v1"));
+        let mut dict = dict.with_origin("base.yml");
+        dict.dict.get_mut("LOCKED").unwrap().mutability = Mutability::Immutable;
+        dict
+    }
+
+    fn override_dict() -> OriginDict {
+        let mut other = OriginDict::new();
+        other.insert("locked", ValueType::from("v2"));
+        other.insert("open", ValueType::from("v2"));
+        other.with_origin("user.yml")
+    }
+
+    #[test]
+    fn test_merge_checked_reports_violations() {
+        let mut dict = base_dict();
+        let violations = dict.merge_checked(&override_dict());
+
+        assert_eq!(violations.len(), 1);
+        let v = &violations[0];
+        assert_eq!(v.key().as_str(), "LOCKED");
+        assert_eq!(v.existing_origin(), &Some("base.yml".to_string()));
+        assert_eq!(v.attempted_origin(), &Some("user.yml".to_string()));
+        assert_eq!(v.attempted_value(), &ValueType::from("v2"));
+        assert!(v.to_string().contains("LOCKED is immutable"));
+
+        assert_eq!(dict.get("LOCKED").unwrap().value(), &ValueType::from("v1"));
+        assert_eq!(dict.get("OPEN").unwrap().value(), &ValueType::from("v2"));
+    }
+
+    #[test]
+    fn test_merge_checked_same_value_is_not_violation() {
+        let mut dict = base_dict();
+        let mut other = OriginDict::new();
+        other.insert("locked", ValueType::from("v1"));
+        assert!(dict.merge_checked(&other).is_empty());
+    }
+
+    #[test]
+    fn test_merge_strict() {
+        let mut dict = base_dict();
+        assert!(dict.merge_strict(&override_dict()).is_err());
+        // 失败时不做任何修改
+        assert_eq!(dict.get("OPEN").unwrap().value(), &ValueType::from("v1"));
+
+        let mut other = OriginDict::new();
+        other.insert("open", ValueType::from("v3"));
+        assert!(dict.merge_strict(&other).is_ok());
+        assert_eq!(dict.get("OPEN").unwrap().value(), &ValueType::from("v3"));
+    }
+}
+
+#[cfg(test)]
+mod namespace_tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_dict_merge_namespaced() {
+        let mut dict = OriginDict::new();
+        dict.insert("port", ValueType::from(80u64));
+
+        let mut redis = OriginDict::new();
+        redis.insert("port", ValueType::from(6379u64));
+        dict.merge_namespaced("redis", &redis.with_origin("redis"));
+
+        assert_eq!(dict.len(), 2);
+        let scoped = dict.get_scoped("redis", "port").unwrap();
+        assert_eq!(scoped.value(), &ValueType::from(6379u64));
+        assert_eq!(scoped.origin(), &Some("redis".to_string()));
+        assert_eq!(
+            dict.get_scoped("mysql", "port").unwrap().value(),
+            &ValueType::from(80u64)
+        );
+
+        let flat = dict.export_flat(FlattenKeys::Join('_'));
+        assert_eq!(flat.get("REDIS_PORT"), Some(&ValueType::from(6379u64)));
+    }
+}
+
 #[cfg(test)]
 mod change_scope_tests {
     use super::*;
@@ -542,6 +740,7 @@ mod change_scope_tests {
             origin: None,
             value: ValueType::from("test"),
             mutability: Mutability::Immutable,
+            location: None,
         };
         assert!(!immutable_value.is_mutable());
 
@@ -549,6 +748,7 @@ mod change_scope_tests {
             origin: None,
             value: ValueType::from("test"),
             mutability: Mutability::System,
+            location: None,
         };
         assert!(public_value.is_mutable());
 
@@ -556,6 +756,7 @@ mod change_scope_tests {
             origin: None,
             value: ValueType::from("test"),
             mutability: Mutability::Module,
+            location: None,
         };
         assert!(model_value.is_mutable());
     }
@@ -604,6 +805,7 @@ mod change_scope_tests {
             origin: Some("test_origin".to_string()),
             value: ValueType::from("prefix_${TEST_VAR}_suffix"),
             mutability: Mutability::Immutable,
+            location: None,
         };
 
         let evaluated = value.env_eval(&env_dict);
@@ -622,6 +824,7 @@ mod change_scope_tests {
             origin: Some("test_origin".to_string()),
             value: ValueType::from("test_value"),
             mutability: Mutability::System,
+            location: None,
         };
 
         // 默认的 Public scope 应该被跳过序列化
@@ -633,6 +836,7 @@ mod change_scope_tests {
             origin: Some("test_origin".to_string()),
             value: ValueType::from("test_value"),
             mutability: Mutability::Immutable,
+            location: None,
         };
 
         let json_immutable = serde_json::to_string(&immutable_value).unwrap();
@@ -0,0 +1,248 @@
+use std::{fs, net::IpAddr};
+
+use orion_error::{ErrorOwe, ErrorWith, StructError};
+use winnow::Parser;
+
+use super::{
+    UpperKey, ValueDict, ValueObj, ValueType,
+    error::{VarsReason, VarsResult},
+    parse::take_value,
+};
+
+fn override_err<T>(msg: String) -> VarsResult<T> {
+    Err(StructError::from(VarsReason::Override(msg)))
+}
+
+fn split_assign(arg: &str) -> VarsResult<(&str, &str)> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim(), value)),
+        _ => override_err(format!("`{arg}`: expected key=value")),
+    }
+}
+
+/// 推断 `--set` 值的类型
+///
+/// 以 `"`、`[`、`{` 开头时按变量值语法解析（如 `[1, 2]`、`{ a: "A" }`）；
+/// 否则依次尝试 bool、整数、浮点、IP，都不匹配时为去掉两端空白的字符串。
+/// 无符号整数为 `Number`；带小数点或负号的数（如 `0.5`、`-1`、`-1.5`）为 `Float`。
+pub fn infer_value(raw: &str) -> VarsResult<ValueType> {
+    let trimmed = raw.trim();
+    if trimmed.starts_with(['"', '[', '{']) {
+        return take_value
+            .parse(trimmed)
+            .map_err(|e| StructError::from(VarsReason::Override(format!("`{raw}`: {e}"))));
+    }
+    let value = if let Ok(b) = trimmed.parse::<bool>() {
+        ValueType::Bool(b)
+    } else if let Ok(n) = trimmed.parse::<u64>() {
+        ValueType::Number(n)
+    } else if (trimmed.contains('.') || trimmed.starts_with('-'))
+        && let Ok(f) = trimmed.parse::<f64>()
+        && f.is_finite()
+    {
+        ValueType::Float(f)
+    } else if let Ok(ip) = trimmed.parse::<IpAddr>() {
+        ValueType::Ip(ip)
+    } else {
+        ValueType::String(trimmed.to_string())
+    };
+    Ok(value)
+}
+
+/// 解析 `--set key=value`，值类型见 [`infer_value`]
+pub fn parse_set(arg: &str) -> VarsResult<(String, ValueType)> {
+    let (key, value) = split_assign(arg)?;
+    Ok((key.to_string(), infer_value(value)?))
+}
+
+/// 解析 `--set-file key=path`，文件内容原样作为字符串值
+pub fn parse_set_file(arg: &str) -> VarsResult<(String, ValueType)> {
+    let (key, path) = split_assign(arg)?;
+    let content = fs::read_to_string(path.trim())
+        .owe_sys()
+        .want("read --set-file")
+        .with(arg.to_string())?;
+    Ok((key.to_string(), ValueType::String(content)))
+}
+
+// 嵌套对象的键与顶层键一样按 `UpperKey` 折叠比较，已有同名键时沿用其原有写法
+fn obj_key(obj: &ValueObj, part: &str) -> String {
+    let folded = UpperKey::fold(part);
+    obj.keys()
+        .find(|k| UpperKey::fold(k) == folded)
+        .cloned()
+        .unwrap_or_else(|| part.to_string())
+}
+
+fn set_in_obj(obj: &mut ValueObj, path: &str, parts: &[&str], value: ValueType) -> VarsResult<()> {
+    let Some((last, parents)) = parts.split_last() else {
+        return override_err(format!("`{path}`: empty path"));
+    };
+    let mut cur = obj;
+    for part in parents {
+        let entry = cur
+            .entry(obj_key(cur, part))
+            .or_insert_with(|| ValueType::Obj(ValueObj::new()));
+        cur = match entry {
+            ValueType::Obj(inner) => inner,
+            other => {
+                return override_err(format!(
+                    "`{path}`: `{part}` is {}, not Obj",
+                    other.variant_name()
+                ));
+            }
+        };
+    }
+    cur.insert(obj_key(cur, last), value);
+    Ok(())
+}
+
+impl ValueDict {
+    /// 按点分路径设置值
+    ///
+    /// 已存在同名的扁平键（如命名空间键 `APP.PORT`）时直接替换；否则首段为顶层键，
+    /// 其余段写入嵌套对象，缺失的中间对象自动创建。嵌套对象的键同样不区分大小写，
+    /// 已有的键保持原写法。路径途经非对象值时报错。
+    pub fn set_path(&mut self, path: &str, value: ValueType) -> VarsResult<()> {
+        if self.get_case_insensitive(path).is_some() || !path.contains('.') {
+            self.insert(path, value);
+            return Ok(());
+        }
+        let parts: Vec<&str> = path.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return override_err(format!("`{path}`: empty path segment"));
+        }
+        let Some((head, rest)) = parts.split_first() else {
+            return override_err(format!("`{path}`: empty path"));
+        };
+        let mut obj = match self.get_case_insensitive(head) {
+            None => ValueObj::new(),
+            Some(ValueType::Obj(obj)) => obj.clone(),
+            Some(other) => {
+                return override_err(format!(
+                    "`{path}`: `{head}` is {}, not Obj",
+                    other.variant_name()
+                ));
+            }
+        };
+        set_in_obj(&mut obj, path, rest, value)?;
+        self.insert(*head, ValueType::Obj(obj));
+        Ok(())
+    }
+
+    /// 依次应用 `--set key=value` 参数，后出现的覆盖先出现的
+    pub fn apply_set_args<S: AsRef<str>>(&mut self, args: &[S]) -> VarsResult<()> {
+        for arg in args {
+            let (key, value) = parse_set(arg.as_ref())?;
+            self.set_path(&key, value)?;
+        }
+        Ok(())
+    }
+
+    /// 依次应用 `--set-file key=path` 参数
+    pub fn apply_set_file_args<S: AsRef<str>>(&mut self, args: &[S]) -> VarsResult<()> {
+        for arg in args {
+            let (key, value) = parse_set_file(arg.as_ref())?;
+            self.set_path(&key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_infer_value() {
+        assert_eq!(infer_value("true").unwrap(), ValueType::Bool(true));
+        assert_eq!(infer_value("8080").unwrap(), ValueType::Number(8080));
+        assert_eq!(infer_value("0.5").unwrap(), ValueType::Float(0.5));
+        assert_eq!(
+            infer_value("10.0.0.1").unwrap(),
+            ValueType::Ip("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(infer_value("-1").unwrap(), ValueType::Float(-1.0));
+        assert_eq!(infer_value("-1.5").unwrap(), ValueType::Float(-1.5));
+        assert_eq!(infer_value("-inf").unwrap(), ValueType::from("-inf"));
+        assert_eq!(infer_value("-v").unwrap(), ValueType::from("-v"));
+        assert_eq!(infer_value("a=b").unwrap(), ValueType::from("a=b"));
+        assert_eq!(infer_value(" demo ").unwrap(), ValueType::from("demo"));
+        assert_eq!(
+            infer_value("\" demo \"").unwrap(),
+            ValueType::from(" demo ")
+        );
+        assert_eq!(infer_value("\"8080\"").unwrap(), ValueType::from("8080"));
+        assert_eq!(
+            infer_value("[1, 2]").unwrap(),
+            ValueType::List(vec![ValueType::from(1u64), ValueType::from(2u64)])
+        );
+        let err = infer_value("[1, 2").unwrap_err();
+        assert!(err.to_string().contains("[1, 2"));
+    }
+
+    #[test]
+    fn test_apply_set_args() {
+        let mut db = ValueObj::new();
+        db.insert("host".into(), ValueType::from("localhost"));
+        let mut dict = ValueDict::new();
+        dict.insert("db", ValueType::Obj(db));
+        dict.insert("app.port", ValueType::from(80u64));
+        dict.insert("name", ValueType::from("demo"));
+
+        dict.apply_set_args(&[
+            "db.port=5432",
+            "app.port=8080",
+            "tls.cert.path=/etc/cert.pem",
+            "replicas=3",
+            "replicas=5",
+        ])
+        .unwrap();
+
+        let db = dict.get("DB").unwrap();
+        let ValueType::Obj(db) = db else {
+            panic!("DB should stay an object");
+        };
+        assert_eq!(db.get("host"), Some(&ValueType::from("localhost")));
+        assert_eq!(db.get("port"), Some(&ValueType::from(5432u64)));
+
+        dict.apply_set_args(&["DB.Port=6432", "db.HOST=db.local"])
+            .unwrap();
+        let ValueType::Obj(db) = dict.get("DB").unwrap() else {
+            panic!("DB should stay an object");
+        };
+        assert_eq!(db.len(), 2);
+        assert_eq!(db.get("port"), Some(&ValueType::from(6432u64)));
+        assert_eq!(db.get("host"), Some(&ValueType::from("db.local")));
+        assert_eq!(dict.get("APP.PORT"), Some(&ValueType::from(8080u64)));
+        assert_eq!(dict.get("REPLICAS"), Some(&ValueType::from(5u64)));
+        let ValueType::Obj(tls) = dict.get("TLS").unwrap() else {
+            panic!("TLS should be an object");
+        };
+        assert!(matches!(tls.get("cert"), Some(ValueType::Obj(_))));
+
+        let err = dict.apply_set_args(&["name.first=x"]).unwrap_err();
+        assert!(err.to_string().contains("`name` is String, not Obj"));
+        let err = dict.apply_set_args(&["novalue"]).unwrap_err();
+        assert!(err.to_string().contains("expected key=value"));
+        assert!(dict.apply_set_args(&["a..b=1"]).is_err());
+        let err = set_in_obj(&mut ValueObj::new(), "x", &[], ValueType::from(1u64)).unwrap_err();
+        assert!(err.to_string().contains("empty path"));
+    }
+
+    #[test]
+    fn test_apply_set_file_args() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("ca.pem");
+        fs::write(&file, "-----BEGIN-----\n").unwrap();
+        let mut dict = ValueDict::new();
+        dict.apply_set_file_args(&[format!("tls.ca={}", file.display())])
+            .unwrap();
+        let ValueType::Obj(tls) = dict.get("TLS").unwrap() else {
+            panic!("TLS should be an object");
+        };
+        assert_eq!(tls.get("ca"), Some(&ValueType::from("-----BEGIN-----\n")));
+        assert!(dict.apply_set_file_args(&["x=/nonexistent/file"]).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use std::net::IpAddr;
+
+use getset::{Getters, WithSetters};
+use orion_error::StructError;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    ValueObj, ValueType, ValueVec,
+    error::{VarsReason, VarsResult},
+};
+
+/// 值的类型标识，与 `ValueType::variant_name()` 一一对应
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    String,
+    Bool,
+    Number,
+    Float,
+    Ip,
+    Obj,
+    List,
+}
+
+impl ValueKind {
+    /// 判断值是否符合该类型
+    ///
+    /// 反序列化时 IP 会被解析为字符串，因此 `Ip` 也接受可解析为 IP 的字符串；
+    /// `Float` 同时接受整数。
+    pub fn accepts(&self, value: &ValueType) -> bool {
+        match (self, value) {
+            (ValueKind::String, ValueType::String(_)) => true,
+            (ValueKind::Bool, ValueType::Bool(_)) => true,
+            (ValueKind::Number, ValueType::Number(_)) => true,
+            (ValueKind::Float, ValueType::Float(_) | ValueType::Number(_)) => true,
+            (ValueKind::Ip, ValueType::Ip(_)) => true,
+            (ValueKind::Ip, ValueType::String(s)) => s.parse::<IpAddr>().is_ok(),
+            (ValueKind::Obj, ValueType::Obj(_)) => true,
+            (ValueKind::List, ValueType::List(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// 对应的 JSON Schema `type`
+    pub fn json_type(&self) -> &'static str {
+        match self {
+            ValueKind::String | ValueKind::Ip => "string",
+            ValueKind::Bool => "boolean",
+            ValueKind::Number => "integer",
+            ValueKind::Float => "number",
+            ValueKind::Obj => "object",
+            ValueKind::List => "array",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueKind::String => "String",
+            ValueKind::Bool => "Bool",
+            ValueKind::Number => "Number",
+            ValueKind::Float => "Float",
+            ValueKind::Ip => "Ip",
+            ValueKind::Obj => "Obj",
+            ValueKind::List => "List",
+        }
+    }
+}
+
+impl From<&ValueType> for ValueKind {
+    fn from(value: &ValueType) -> Self {
+        match value {
+            ValueType::String(_) => ValueKind::String,
+            ValueType::Bool(_) => ValueKind::Bool,
+            ValueType::Number(_) => ValueKind::Number,
+            ValueType::Float(_) => ValueKind::Float,
+            ValueType::Ip(_) => ValueKind::Ip,
+            ValueType::Obj(_) => ValueKind::Obj,
+            ValueType::List(_) => ValueKind::List,
+        }
+    }
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
+/// 对象字段声明：名称、类型以及是否必填
+#[derive(Getters, WithSetters, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[getset(get = "pub")]
+pub struct FieldSchema {
+    name: String,
+    #[serde(rename = "type")]
+    kind: ValueKind,
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "is_false")]
+    required: bool,
+}
+
+impl FieldSchema {
+    pub fn new<S: Into<String>>(name: S, kind: ValueKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            required: false,
+        }
+    }
+}
+
+/// `ValueObj` 的结构声明，序列化为字段列表
+#[derive(Getters, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
+#[getset(get = "pub")]
+#[serde(transparent)]
+pub struct ObjSchema {
+    fields: Vec<FieldSchema>,
+}
+
+impl From<Vec<FieldSchema>> for ObjSchema {
+    fn from(fields: Vec<FieldSchema>) -> Self {
+        Self { fields }
+    }
+}
+
+impl ObjSchema {
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// 生成 JSON Schema 的 `properties` 与 `required`
+    pub fn json_properties(&self) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+        for field in &self.fields {
+            properties.insert(
+                field.name.clone(),
+                serde_json::json!({ "type": field.kind.json_type() }),
+            );
+            if field.required {
+                required.push(field.name.clone());
+            }
+        }
+        (properties, required)
+    }
+
+    /// 按声明校验对象：必填字段存在、字段类型匹配、不允许未声明字段
+    ///
+    /// `path` 用于错误信息定位，如 `DB.port: expected Number, found String`
+    pub fn validate(&self, path: &str, obj: &ValueObj) -> VarsResult<()> {
+        for field in &self.fields {
+            match obj.get(&field.name) {
+                Some(value) if !field.kind.accepts(value) => {
+                    return Err(StructError::from(VarsReason::Schema(format!(
+                        "{path}.{}: expected {}, found {}",
+                        field.name,
+                        field.kind.name(),
+                        value.variant_name()
+                    ))));
+                }
+                None if field.required => {
+                    return Err(StructError::from(VarsReason::Schema(format!(
+                        "{path}.{}: missing required field",
+                        field.name
+                    ))));
+                }
+                _ => {}
+            }
+        }
+        if let Some(key) = obj.keys().find(|k| self.field(k).is_none()) {
+            return Err(StructError::from(VarsReason::Schema(format!(
+                "{path}.{key}: undeclared field"
+            ))));
+        }
+        Ok(())
+    }
+}
+
+/// 字符串元素需整体匹配的正则（自动加 `^...$`）
+///
+/// 构造与反序列化时编译，非法表达式在加载阶段报错；按原始文本比较与序列化。
+#[derive(Clone, Debug)]
+pub struct ListPattern {
+    source: String,
+    regex: regex::Regex,
+}
+
+impl ListPattern {
+    pub fn new<S: Into<String>>(source: S) -> VarsResult<Self> {
+        let source = source.into();
+        let regex = regex::Regex::new(&format!("^(?:{source})$")).map_err(|e| {
+            StructError::from(VarsReason::Schema(format!(
+                "invalid pattern `{source}`: {e}"
+            )))
+        })?;
+        Ok(Self { source, regex })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for ListPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl serde::Serialize for ListPattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ListPattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Self::new(source).map_err(serde::de::Error::custom)
+    }
+}
+
+/// `ValueVec` 的元素声明：元素类型，以及字符串元素需匹配的正则
+#[derive(Getters, WithSetters, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[getset(get = "pub")]
+pub struct ListSchema {
+    #[serde(rename = "type")]
+    kind: ValueKind,
+    #[getset(get = "pub", set_with = "pub")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pattern: Option<ListPattern>,
+}
+
+impl ListSchema {
+    pub fn new(kind: ValueKind) -> Self {
+        Self {
+            kind,
+            pattern: None,
+        }
+    }
+
+    /// 生成 JSON Schema 的 `items`
+    pub fn json_items(&self) -> serde_json::Value {
+        let mut items = serde_json::json!({ "type": self.kind.json_type() });
+        if let Some(pattern) = &self.pattern {
+            items["pattern"] = pattern.as_str().into();
+        }
+        items
+    }
+
+    /// 逐个校验元素，错误信息带下标，如 `RETRIES[2]: expected Number, found String`
+    ///
+    /// `pattern` 按整个字符串匹配，只作用于字符串元素。
+    pub fn validate(&self, path: &str, list: &ValueVec) -> VarsResult<()> {
+        for (idx, item) in list.iter().enumerate() {
+            if !self.kind.accepts(item) {
+                return Err(StructError::from(VarsReason::Schema(format!(
+                    "{path}[{idx}]: expected {}, found {}",
+                    self.kind.name(),
+                    item.variant_name()
+                ))));
+            }
+            if let (Some(pattern), ValueType::String(s)) = (&self.pattern, item)
+                && !pattern.is_match(s)
+            {
+                return Err(StructError::from(VarsReason::Schema(format!(
+                    "{path}[{idx}]: `{s}` does not match `{}`",
+                    pattern.as_str()
+                ))));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db_schema() -> ObjSchema {
+        ObjSchema::from(vec![
+            FieldSchema::new("host", ValueKind::String).with_required(true),
+            FieldSchema::new("port", ValueKind::Number).with_required(true),
+            FieldSchema::new("addr", ValueKind::Ip),
+        ])
+    }
+
+    #[test]
+    fn test_value_kind_accepts() {
+        assert!(ValueKind::String.accepts(&ValueType::from("a")));
+        assert!(ValueKind::Float.accepts(&ValueType::from(1u64)));
+        assert!(ValueKind::Ip.accepts(&ValueType::from("10.0.0.1")));
+        assert!(!ValueKind::Ip.accepts(&ValueType::from("localhost")));
+        assert!(!ValueKind::Number.accepts(&ValueType::from("1")));
+    }
+
+    #[test]
+    fn test_obj_schema_validate() {
+        let schema = db_schema();
+        let mut obj = ValueObj::new();
+        obj.insert("host".into(), ValueType::from("localhost"));
+        obj.insert("port".into(), ValueType::from(5432u64));
+        assert!(schema.validate("DB", &obj).is_ok());
+
+        obj.insert("port".into(), ValueType::from("5432"));
+        let err = schema.validate("DB", &obj).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("DB.port: expected Number, found String")
+        );
+
+        obj.shift_remove("port");
+        assert!(schema.validate("DB", &obj).is_err());
+
+        obj.insert("port".into(), ValueType::from(5432u64));
+        obj.insert("user".into(), ValueType::from("root"));
+        assert!(schema.validate("DB", &obj).is_err());
+    }
+
+    #[test]
+    fn test_obj_schema_serde() {
+        let yaml = r#"
+- name: host
+  type: string
+  required: true
+- name: port
+  type: number
+"#;
+        let schema: ObjSchema = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schema.fields().len(), 2);
+        assert!(*schema.field("host").unwrap().required());
+        assert!(!*schema.field("port").unwrap().required());
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"name":"host","type":"string","required":true},{"name":"port","type":"number"}]"#
+        );
+    }
+
+    #[test]
+    fn test_list_schema_validate() {
+        let retries = ListSchema::new(ValueKind::Number);
+        let list = vec![
+            ValueType::from(1u64),
+            ValueType::from(2u64),
+            ValueType::from("3"),
+        ];
+        let err = retries.validate("RETRIES", &list).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("RETRIES[2]: expected Number, found String")
+        );
+        assert!(retries.validate("RETRIES", &list[..2].to_vec()).is_ok());
+
+        let hosts = ListSchema::new(ValueKind::String)
+            .with_pattern(Some(ListPattern::new(r"[a-z0-9.-]+").unwrap()));
+        let list = vec![ValueType::from("a.example.com"), ValueType::from("B_HOST")];
+        let err = hosts.validate("HOSTS", &list).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("HOSTS[1]: `B_HOST` does not match")
+        );
+
+        let err = ListPattern::new("(").unwrap_err();
+        assert!(err.to_string().contains("invalid pattern `(`"));
+        // 非法正则在反序列化时即失败
+        let err =
+            serde_yaml::from_str::<ListSchema>("{ type: string, pattern: \"(\" }").unwrap_err();
+        assert!(err.to_string().contains("invalid pattern"));
+        let loaded: ListSchema =
+            serde_yaml::from_str("{ type: string, pattern: \"[a-z0-9.-]+\" }").unwrap();
+        assert_eq!(loaded, hosts);
+        assert_eq!(
+            hosts.json_items(),
+            serde_json::json!({ "type": "string", "pattern": "[a-z0-9.-]+" })
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use orion_error::{ErrorOwe, ErrorWith, StructError};
+
+use super::error::{VarsReason, VarsResult};
+
+/// 外部密钥来源，在 env_eval 时解析 `${scheme:reference}`
+///
+/// 例如 `${vault:secret/data/app#token}` 交给 scheme 为 `vault` 的解析器处理。
+/// 只有已注册的 scheme 才按密钥解析，其余仍按 `${VAR:default}` 处理；
+/// Vault、SOPS 等具体后端由独立的 crate 实现本 trait。
+pub trait SecretResolver: Send + Sync {
+    /// 引用前缀，大小写不敏感
+    fn scheme(&self) -> &str;
+    /// 解析 `:` 之后的引用，返回明文
+    fn resolve(&self, reference: &str) -> VarsResult<String>;
+}
+
+static RESOLVERS: LazyLock<RwLock<Vec<Arc<dyn SecretResolver>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// 注册全局密钥解析器，替换同 scheme 的已有解析器
+pub fn register_secret_resolver<R: SecretResolver + 'static>(resolver: R) {
+    let mut resolvers = RESOLVERS.write().unwrap_or_else(|e| e.into_inner());
+    resolvers.retain(|r| !r.scheme().eq_ignore_ascii_case(resolver.scheme()));
+    resolvers.push(Arc::new(resolver));
+}
+
+/// 移除 scheme 对应的解析器，返回是否存在
+pub fn unregister_secret_resolver(scheme: &str) -> bool {
+    let mut resolvers = RESOLVERS.write().unwrap_or_else(|e| e.into_inner());
+    let before = resolvers.len();
+    resolvers.retain(|r| !r.scheme().eq_ignore_ascii_case(scheme));
+    resolvers.len() != before
+}
+
+pub(crate) fn find_secret_resolver(scheme: &str) -> Option<Arc<dyn SecretResolver>> {
+    RESOLVERS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|r| r.scheme().eq_ignore_ascii_case(scheme))
+        .cloned()
+}
+
+/// 基于文件的示例解析器：`${file:path}` 读取整个文件，`${file:path#a.b}` 读取
+/// YAML/JSON 文件中的字段。路径相对于 `root`，绝对路径与 `..` 一律拒绝。
+#[derive(Clone, Debug)]
+pub struct FileSecretResolver {
+    scheme: String,
+    root: PathBuf,
+}
+
+impl FileSecretResolver {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self {
+            scheme: "file".into(),
+            root: root.into(),
+        }
+    }
+
+    /// 使用自定义 scheme，如 `secret`
+    pub fn with_scheme<S: Into<String>>(mut self, scheme: S) -> Self {
+        self.scheme = scheme.into();
+        self
+    }
+}
+
+impl SecretResolver for FileSecretResolver {
+    fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    fn resolve(&self, reference: &str) -> VarsResult<String> {
+        let (path, field) = match reference.split_once('#') {
+            Some((path, field)) => (path, Some(field)),
+            None => (reference, None),
+        };
+        // 限制在 root 之内：绝对路径会替换 root，`..` 会跳出 root
+        let confined = Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if path.is_empty() || !confined {
+            return Err(StructError::from(VarsReason::Secret(format!(
+                "{path}: must be a relative path inside the secret root"
+            ))));
+        }
+        let path = self.root.join(path);
+        let content = fs::read_to_string(&path)
+            .owe_sys()
+            .want("read secret file")
+            .with(path.display().to_string())?;
+        let Some(field) = field else {
+            return Ok(content.trim_end().to_string());
+        };
+        let doc: serde_yaml::Value = serde_yaml::from_str(&content)
+            .owe(VarsReason::Format)
+            .with(path.display().to_string())?;
+        let mut cur = &doc;
+        for part in field.split('.') {
+            cur = cur.get(part).ok_or_else(|| {
+                StructError::from(VarsReason::Secret(format!(
+                    "{}: field `{field}` not found",
+                    path.display()
+                )))
+            })?;
+        }
+        match cur {
+            serde_yaml::Value::String(s) => Ok(s.clone()),
+            serde_yaml::Value::Number(n) => Ok(n.to_string()),
+            serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+            _ => Err(StructError::from(VarsReason::Secret(format!(
+                "{}: field `{field}` is not a scalar",
+                path.display()
+            )))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::{
+        EnvDict, EnvEvaluable, ValueType, env_eval::try_expand_env_vars, extract_env_var_names,
+    };
+    use tempfile::TempDir;
+
+    struct StaticResolver;
+
+    impl SecretResolver for StaticResolver {
+        fn scheme(&self) -> &str {
+            "teststatic"
+        }
+        fn resolve(&self, reference: &str) -> VarsResult<String> {
+            match reference {
+                "secret/data/app#token" => Ok("s3cr3t".into()),
+                _ => Err(StructError::from(VarsReason::Secret(format!(
+                    "{reference}: not found"
+                )))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_secret_in_env_eval() {
+        register_secret_resolver(StaticResolver);
+        let dict = EnvDict::new();
+        let value = ValueType::from("token=${teststatic:secret/data/app#token}").env_eval(&dict);
+        assert_eq!(value, ValueType::from("token=s3cr3t"));
+        assert_eq!(
+            extract_env_var_names("${teststatic:secret/data/app#token}-${USER}"),
+            vec!["USER"]
+        );
+
+        // 失败时宽松模式保持原样，严格模式返回错误
+        assert_eq!(
+            "${teststatic:missing}".to_string().env_eval(&dict),
+            "${teststatic:missing}"
+        );
+        let err = try_expand_env_vars(&dict, "${teststatic:missing}").unwrap_err();
+        assert!(err.to_string().contains("missing: not found"));
+
+        assert!(unregister_secret_resolver("TESTSTATIC"));
+        // 未注册的 scheme 回到默认值语法
+        assert_eq!(
+            "${teststatic:fallback}".to_string().env_eval(&dict),
+            "fallback"
+        );
+    }
+
+    #[test]
+    fn test_file_secret_resolver() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("token"), "abc123\n").unwrap();
+        fs::write(
+            dir.path().join("app.yml"),
+            "db:\n  password: pa55\n  port: 5432\n",
+        )
+        .unwrap();
+        let resolver = FileSecretResolver::new(dir.path()).with_scheme("testfile");
+        assert_eq!(resolver.resolve("token").unwrap(), "abc123");
+        assert_eq!(resolver.resolve("app.yml#db.password").unwrap(), "pa55");
+        assert_eq!(resolver.resolve("app.yml#db.port").unwrap(), "5432");
+        assert!(resolver.resolve("app.yml#db.user").is_err());
+        assert!(resolver.resolve("app.yml#db").is_err());
+        assert!(resolver.resolve("missing").is_err());
+
+        // 不允许读取 root 之外的文件
+        let inner = dir.path().join("inner");
+        fs::create_dir(&inner).unwrap();
+        let resolver = FileSecretResolver::new(&inner);
+        let outside = dir.path().join("token");
+        for reference in [outside.to_str().unwrap(), "../token", "a/../../token", ""] {
+            let err = resolver.resolve(reference).unwrap_err();
+            assert!(err.to_string().contains("inside the secret root"));
+        }
+    }
+}
@@ -1,28 +1,39 @@
-use std::{
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
     fmt::{Display, Formatter},
     net::IpAddr,
 };
+#[cfg(feature = "std")]
+use std::path::PathBuf;
 
+#[cfg(feature = "std")]
 use crate::vars::{
     error::{VarsReason, VarsResult},
     parse::{take_value_map, take_value_vec},
 };
 
-use super::{
-    ValueDict,
-    env_eval::{expand_env_vars, extract_env_var_names},
-};
+use super::ValueDict;
+#[cfg(feature = "std")]
+use super::env_eval::{expand_env_vars, extract_env_var_names};
 use derive_more::From;
 use indexmap::IndexMap;
+#[cfg(feature = "std")]
 use orion_error::{ErrorOwe, ErrorWith};
 use serde_derive::{Deserialize, Serialize};
+#[cfg(feature = "std")]
 use winnow::Parser;
 
 pub type EnvDict = ValueDict;
+#[cfg(feature = "std")]
 pub trait EnvEvaluable<T> {
     fn env_eval(self, dict: &EnvDict) -> T;
 }
 
+#[cfg(feature = "std")]
 /// Trait to check if a value contains environment variable placeholders
 /// that need evaluation (e.g., `${VAR_NAME}` or `${VAR_NAME:default}`)
 pub trait EnvChecker {
@@ -34,6 +45,7 @@ pub trait EnvChecker {
     fn list_env_vars(&self) -> Vec<String>;
 }
 
+#[cfg(feature = "std")]
 impl EnvChecker for String {
     fn needs_env_eval(&self) -> bool {
         self.contains("${")
@@ -44,6 +56,7 @@ impl EnvChecker for String {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvChecker for &str {
     fn needs_env_eval(&self) -> bool {
         self.contains("${")
@@ -54,6 +67,29 @@ impl EnvChecker for &str {
     }
 }
 
+#[cfg(feature = "std")]
+impl EnvChecker for PathBuf {
+    fn needs_env_eval(&self) -> bool {
+        self.to_str().is_some_and(|s| s.needs_env_eval())
+    }
+
+    fn list_env_vars(&self) -> Vec<String> {
+        self.to_str().map_or(Vec::new(), extract_env_var_names)
+    }
+}
+
+#[cfg(feature = "std")]
+impl EnvChecker for Option<PathBuf> {
+    fn needs_env_eval(&self) -> bool {
+        self.as_ref().is_some_and(|p| p.needs_env_eval())
+    }
+
+    fn list_env_vars(&self) -> Vec<String> {
+        self.as_ref().map_or(Vec::new(), |p| p.list_env_vars())
+    }
+}
+
+#[cfg(feature = "std")]
 impl EnvChecker for Option<String> {
     fn needs_env_eval(&self) -> bool {
         self.as_ref().is_some_and(|s| s.needs_env_eval())
@@ -64,6 +100,7 @@ impl EnvChecker for Option<String> {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvChecker for Option<&str> {
     fn needs_env_eval(&self) -> bool {
         self.is_some_and(|s| s.needs_env_eval())
@@ -74,33 +111,125 @@ impl EnvChecker for Option<&str> {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvEvaluable<String> for String {
     fn env_eval(self, dict: &EnvDict) -> String {
         expand_env_vars(dict, self.as_str())
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvEvaluable<Option<String>> for Option<String> {
     fn env_eval(self, dict: &EnvDict) -> Option<String> {
         self.map(|x| expand_env_vars(dict, x.as_str()))
     }
 }
 
-pub type ValueObj = IndexMap<String, ValueType>;
+#[cfg(feature = "std")]
+// Windows 下变量值中常混用 `/`，展开后统一为本平台分隔符；其他平台 `\` 是合法文件名字符，保持不变
+fn normalize_separator(path: String) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(path.replace('/', std::path::MAIN_SEPARATOR_STR))
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+#[cfg(feature = "std")]
+/// 路径中的 `${VAR}` 按字符串展开；非 UTF-8 路径原样返回
+impl EnvEvaluable<PathBuf> for PathBuf {
+    fn env_eval(self, dict: &EnvDict) -> PathBuf {
+        match self.to_str() {
+            Some(s) if s.needs_env_eval() => normalize_separator(expand_env_vars(dict, s)),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl EnvEvaluable<Option<PathBuf>> for Option<PathBuf> {
+    fn env_eval(self, dict: &EnvDict) -> Option<PathBuf> {
+        self.map(|x| x.env_eval(dict))
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) type ValueHasher = std::hash::RandomState;
+
+/// `alloc` 构建下 [`ValueObj`] 使用的哈希器
+///
+/// `no_std` 没有随机种子的 `RandomState`，改用固定的 FNV-1a；
+/// 键来自不可信输入时可能受哈希碰撞攻击，应在 `std` 构建下处理。
+#[cfg(not(feature = "std"))]
+pub type ValueHasher = core::hash::BuildHasherDefault<FnvHasher>;
+
+/// FNV-1a 64 位哈希，见 [`ValueHasher`]
+#[cfg(not(feature = "std"))]
+#[derive(Clone, Copy, Debug)]
+pub struct FnvHasher(u64);
+
+#[cfg(not(feature = "std"))]
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+#[cfg(not(feature = "std"))]
+impl core::hash::Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+pub type ValueObj = IndexMap<String, ValueType, ValueHasher>;
 pub type ValueVec = Vec<ValueType>;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// 大写折叠的键，序列化为普通字符串；反序列化时同样折叠为大写
+///
+/// 实现了 `Borrow<str>`，`IndexMap<UpperKey, _>` 可直接用已折叠的 `&str` 查找，
+/// 配合 [`UpperKey::fold`] 在键本身已是大写时无需分配。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 #[serde(transparent)]
 pub struct UpperKey(String);
 
 impl UpperKey {
     fn new<S: Into<String>>(key: S) -> Self {
-        Self(key.into().to_uppercase())
+        let key = key.into();
+        match Self::fold(&key) {
+            Cow::Borrowed(_) => Self(key),
+            Cow::Owned(upper) => Self(upper),
+        }
     }
 
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// 按 `UpperKey` 的规则折叠，已是大写时原样借用
+    pub fn fold(key: &str) -> Cow<'_, str> {
+        let folded = key.chars().all(|c| {
+            let mut upper = c.to_uppercase();
+            upper.next() == Some(c) && upper.next().is_none()
+        });
+        if folded {
+            Cow::Borrowed(key)
+        } else {
+            Cow::Owned(key.to_uppercase())
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for UpperKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
 }
 
 impl<S: Into<String>> From<S> for UpperKey {
@@ -109,13 +238,13 @@ impl<S: Into<String>> From<S> for UpperKey {
     }
 }
 
-impl std::borrow::Borrow<str> for UpperKey {
+impl core::borrow::Borrow<str> for UpperKey {
     fn borrow(&self) -> &str {
         &self.0
     }
 }
 
-impl std::borrow::Borrow<String> for UpperKey {
+impl core::borrow::Borrow<String> for UpperKey {
     fn borrow(&self) -> &String {
         &self.0
     }
@@ -134,7 +263,7 @@ pub enum ValueType {
 }
 
 impl Display for ValueType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             ValueType::String(v) => write!(f, "{v}"),
             ValueType::Bool(v) => write!(f, "{v}"),
@@ -147,6 +276,7 @@ impl Display for ValueType {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvChecker for ValueType {
     fn needs_env_eval(&self) -> bool {
         match self {
@@ -168,6 +298,7 @@ impl EnvChecker for ValueType {
     }
 }
 
+#[cfg(feature = "std")]
 impl EnvEvaluable<ValueType> for ValueType {
     fn env_eval(self, dict: &EnvDict) -> ValueType {
         match self {
@@ -220,6 +351,19 @@ impl ValueType {
             ValueType::List(_) => "List",
         }
     }
+    /// 返回对象键按字典序排列的副本，递归处理嵌套对象与列表元素；列表顺序不变
+    pub fn sorted(&self) -> ValueType {
+        match self {
+            ValueType::Obj(obj) => {
+                let mut obj: ValueObj = obj.iter().map(|(k, v)| (k.clone(), v.sorted())).collect();
+                obj.sort_unstable_keys();
+                ValueType::Obj(obj)
+            }
+            ValueType::List(list) => ValueType::List(list.iter().map(ValueType::sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    #[cfg(feature = "std")]
     pub fn update_from_str(&mut self, s: &str) -> VarsResult<()> {
         let mut input = s;
         match self {
@@ -249,6 +393,7 @@ impl ValueType {
         self.variant_name()
     }
 
+    #[cfg(feature = "std")]
     #[deprecated(note = "renamed to update_from_str()")]
     pub fn update_by_str(&mut self, s: &str) -> VarsResult<()> {
         self.update_from_str(s)
@@ -572,6 +717,53 @@ mod tests {
         assert!(!ValueType::Obj(obj_without_env).needs_env_eval());
     }
 
+    #[test]
+    fn test_upper_key_serde() {
+        use super::{Cow, UpperKey};
+        use crate::vars::ValueDict;
+
+        assert!(matches!(UpperKey::fold("APP.PORT_1"), Cow::Borrowed(_)));
+        assert_eq!(UpperKey::fold("app.port"), "APP.PORT");
+
+        let from_yaml: ValueDict = serde_yaml::from_str("host: a\nPort: 80\n").unwrap();
+        let from_json: ValueDict = serde_json::from_str(r#"{"host": "a", "Port": 80}"#).unwrap();
+        let from_toml: ValueDict = toml::from_str("host = \"a\"\nPort = 80\n").unwrap();
+        // toml 未启用 preserve_order，表的键顺序不保证，按排序后比较
+        for dict in [&from_yaml, &from_json, &from_toml] {
+            let sorted = dict.sorted();
+            let keys: Vec<_> = sorted.keys().map(|k| k.as_str()).collect();
+            assert_eq!(keys, vec!["HOST", "PORT"]);
+            assert_eq!(dict.get("PORT"), Some(&ValueType::from(80u64)));
+        }
+        assert_eq!(from_yaml, from_json);
+        assert_eq!(
+            serde_json::to_string(&from_json).unwrap(),
+            r#"{"HOST":"a","PORT":80}"#
+        );
+    }
+
+    #[test]
+    fn test_pathbuf_env_eval() {
+        let mut dict = EnvDict::new();
+        dict.insert("PROJECT_ROOT", ValueType::from("/work/app"));
+
+        let path = PathBuf::from("${PROJECT_ROOT}/cache/${MODE:debug}");
+        assert!(path.needs_env_eval());
+        assert_eq!(path.list_env_vars(), vec!["PROJECT_ROOT", "MODE"]);
+        let expected: PathBuf = ["/work/app", "cache", "debug"].iter().collect();
+        assert_eq!(path.env_eval(&dict), expected);
+
+        let plain = PathBuf::from("/opt/data");
+        assert!(!plain.needs_env_eval());
+        assert_eq!(plain.clone().env_eval(&dict), plain);
+
+        let opt: Option<PathBuf> = Some(PathBuf::from("${PROJECT_ROOT}"));
+        assert!(opt.needs_env_eval());
+        assert_eq!(opt.env_eval(&dict), Some(PathBuf::from("/work/app")));
+        let none: Option<PathBuf> = None;
+        assert_eq!(none.env_eval(&dict), None);
+    }
+
     #[test]
     fn test_env_eval_with_checker() {
         use super::{EnvChecker, EnvEvaluable};
@@ -0,0 +1,253 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime},
+};
+
+use getset::{Getters, WithSetters};
+
+use super::{
+    EnvDict, VarCollection,
+    error::VarsResult,
+    loader::{Tracked, glob_files, load_tracked},
+};
+
+/// 轮询间隔与去抖时间
+#[derive(Getters, WithSetters, Clone, Debug)]
+#[getset(get = "pub", set_with = "pub")]
+pub struct WatchOptions {
+    interval: Duration,
+    debounce: Duration,
+    /// 解析 `when` 条件所用的字典
+    dict: EnvDict,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            debounce: Duration::from_millis(200),
+            dict: EnvDict::new(),
+        }
+    }
+}
+
+// 各文件的修改时间与大小，以及各通配 include 当前匹配到的文件
+type Signature = (Vec<Option<(SystemTime, u64)>>, Vec<Vec<PathBuf>>);
+
+fn signature(tracked: &Tracked) -> Signature {
+    let files = tracked
+        .files
+        .iter()
+        .map(|f| {
+            fs::metadata(f)
+                .ok()
+                .map(|m| (m.modified().unwrap_or(SystemTime::UNIX_EPOCH), m.len()))
+        })
+        .collect();
+    let globs = tracked
+        .globs
+        .iter()
+        .map(|(dir, name)| glob_files(dir, name).unwrap_or_default())
+        .collect();
+    (files, globs)
+}
+
+/// [`VarCollection::watch`] 返回的句柄，`stop()` 或 drop 时结束后台线程
+pub struct VarsWatcher {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl VarsWatcher {
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for VarsWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+// 分段睡眠，及时响应停止信号
+fn sleep_unless_stopped(stop: &AtomicBool, total: Duration) -> bool {
+    let deadline = Instant::now() + total;
+    while Instant::now() < deadline {
+        if stop.load(Ordering::Relaxed) {
+            return false;
+        }
+        thread::sleep(
+            deadline
+                .saturating_duration_since(Instant::now())
+                .min(Duration::from_millis(50)),
+        );
+    }
+    !stop.load(Ordering::Relaxed)
+}
+
+impl VarCollection {
+    /// 监视变量文件及其 `include` 的文件，变化后重新加载并回调
+    ///
+    /// 通过轮询修改时间与大小检测变化，通配 `include`（如 `common/*.yml`）所在目录中
+    /// 新增或删除匹配的文件同样视为变化；检测到变化后等待文件在 `debounce` 内保持不变再加载。
+    /// 回调收到重新加载（含校验）的结果，加载失败时为 `Err`，之后继续监视。
+    ///
+    /// 返回首次加载的集合与监视句柄；首次加载失败时直接返回错误。
+    pub fn watch<P, F>(
+        path: P,
+        options: WatchOptions,
+        mut on_change: F,
+    ) -> VarsResult<(VarCollection, VarsWatcher)>
+    where
+        P: AsRef<Path>,
+        F: FnMut(VarsResult<VarCollection>) + Send + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let (initial, mut tracked) = load_tracked(&path, options.dict())?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let mut last = signature(&tracked);
+        let handle = thread::spawn(move || {
+            while sleep_unless_stopped(&flag, options.interval) {
+                let mut current = signature(&tracked);
+                if current == last {
+                    continue;
+                }
+                // 去抖：等待写入完成
+                loop {
+                    if !sleep_unless_stopped(&flag, options.debounce) {
+                        return;
+                    }
+                    let next = signature(&tracked);
+                    if next == current {
+                        break;
+                    }
+                    current = next;
+                }
+                match load_tracked(&path, options.dict()) {
+                    Ok((vars, reloaded)) => {
+                        tracked = reloaded;
+                        last = signature(&tracked);
+                        on_change(Ok(vars));
+                    }
+                    Err(e) => {
+                        last = current;
+                        on_change(Err(e));
+                    }
+                }
+            }
+        });
+        Ok((
+            initial,
+            VarsWatcher {
+                stop,
+                handle: Some(handle),
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vars::ValueType;
+    use std::sync::mpsc;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_watch_reload() {
+        let dir = TempDir::new().unwrap();
+        let main = dir.path().join("main.yml");
+        let common = dir.path().join("common.yml");
+        fs::write(&common, "module:\n  - name: shared\n    value: one\n").unwrap();
+        fs::write(&main, "include: [common.yml]\n").unwrap();
+
+        let options = WatchOptions::default()
+            .with_interval(Duration::from_millis(20))
+            .with_debounce(Duration::from_millis(20));
+        let (tx, rx) = mpsc::channel();
+        let (initial, watcher) = VarCollection::watch(&main, options, move |x| {
+            let _ = tx.send(x);
+        })
+        .unwrap();
+        assert_eq!(
+            initial.value_dict().get("SHARED"),
+            Some(&ValueType::from("one"))
+        );
+
+        // 被包含文件的修改同样触发重新加载
+        fs::write(
+            &common,
+            "module:\n  - name: shared\n    value: two-changed\n",
+        )
+        .unwrap();
+        let vars = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(
+            vars.value_dict().get("SHARED"),
+            Some(&ValueType::from("two-changed"))
+        );
+
+        // 非法内容回调错误，之后仍继续监视
+        fs::write(&common, "module: [ {").unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(5)).unwrap().is_err());
+        fs::write(&common, "module:\n  - name: shared\n    value: three\n").unwrap();
+        let vars = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(
+            vars.value_dict().get("SHARED"),
+            Some(&ValueType::from("three"))
+        );
+        watcher.stop();
+
+        assert!(
+            VarCollection::watch(
+                dir.path().join("missing.yml"),
+                WatchOptions::default(),
+                |_| {}
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_watch_new_glob_match() {
+        let dir = TempDir::new().unwrap();
+        let conf = dir.path().join("conf.d");
+        fs::create_dir(&conf).unwrap();
+        fs::write(conf.join("a.yml"), "module:\n  - name: a\n    value: one\n").unwrap();
+        let main = dir.path().join("main.yml");
+        fs::write(&main, "include: [conf.d/*.yml]\n").unwrap();
+
+        let options = WatchOptions::default()
+            .with_interval(Duration::from_millis(20))
+            .with_debounce(Duration::from_millis(20));
+        let (tx, rx) = mpsc::channel();
+        let (initial, watcher) = VarCollection::watch(&main, options, move |x| {
+            let _ = tx.send(x);
+        })
+        .unwrap();
+        assert_eq!(initial.value_dict().get("B"), None);
+
+        // 新增匹配通配的文件触发重新加载
+        fs::write(conf.join("b.yml"), "module:\n  - name: b\n    value: two\n").unwrap();
+        let vars = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(vars.value_dict().get("B"), Some(&ValueType::from("two")));
+        // 不匹配的文件不触发
+        fs::write(conf.join("notes.txt"), "x").unwrap();
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+        watcher.stop();
+    }
+}